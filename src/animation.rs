@@ -0,0 +1,61 @@
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, Frame, ImageFormat, RgbaImage};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Browsers show frames with a delay of 10ms or less for 100ms, and animations are made for that
+const MIN_FRAME_DELAY_MS: u32 = 20;
+const DEFAULT_FRAME_DELAY_MS: u32 = 100;
+
+/// One frame of an animated logo, composited onto the full canvas
+pub struct AnimationFrame {
+    pub image: RgbaImage,
+    pub delay_ms: u32,
+}
+
+/// Frames of an animated GIF, APNG or WebP file. None for still images and files that fail to decode,
+/// which are then shown through the regular single-image path.
+pub fn load_frames(path: &Path) -> Option<Vec<AnimationFrame>> {
+    let format = image::io::Reader::open(path).ok()?.with_guessed_format().ok()?.format()?;
+    let reader = BufReader::new(File::open(path).ok()?);
+
+    let frames: Vec<Frame> = match format {
+        ImageFormat::Gif => GifDecoder::new(reader).ok()?.into_frames().collect_frames().ok()?,
+        ImageFormat::Png => {
+            let decoder = PngDecoder::new(reader).ok()?;
+            if !decoder.is_apng() {
+                return None;
+            }
+            decoder.apng().into_frames().collect_frames().ok()?
+        }
+        ImageFormat::WebP => {
+            let decoder = WebPDecoder::new(reader).ok()?;
+            if !decoder.has_animation() {
+                return None;
+            }
+            decoder.into_frames().collect_frames().ok()?
+        }
+        _ => return None,
+    };
+
+    if frames.len() < 2 {
+        return None;
+    }
+
+    Some(
+        frames
+            .into_iter()
+            .map(|frame| {
+                let (numerator, denominator) = frame.delay().numer_denom_ms();
+                let delay_ms = numerator.checked_div(denominator).unwrap_or(0);
+                AnimationFrame {
+                    delay_ms: if delay_ms < MIN_FRAME_DELAY_MS { DEFAULT_FRAME_DELAY_MS } else { delay_ms },
+                    image: frame.into_buffer(),
+                }
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// A text logo in the style of neofetch: `$1`..`$9` switch to the configured colors
+pub struct AsciiLogo {
+    pub lines: Vec<String>,
+    /// Colors for `$1`.. when none are configured, as ANSI color numbers
+    pub default_colors: &'static [&'static str],
+}
+
+struct BuiltinLogo {
+    names: &'static [&'static str],
+    colors: &'static [&'static str],
+    art: &'static str,
+}
+
+/// Built-in logos, looked up by /etc/os-release `ID` and `ID_LIKE`
+const BUILTIN_LOGOS: &[BuiltinLogo] = &[
+    BuiltinLogo {
+        names: &["arch", "archlinux"],
+        colors: &["6", "6"],
+        art: r#"
+$1                   -`
+$1                  .o+`
+$1                 `ooo/
+$1                `+oooo:
+$1               `+oooooo:
+$1               -+oooooo+:
+$1             `/:-:++oooo+:
+$1            `/++++/+++++++:
+$1           `/++++++++++++++:
+$1          `/+++o$2oooooooo$1oooo/`
+$2         $1./$2ooosssso++osssssso$1+`
+$2        .oossssso-````/ossssss+`
+$2       -osssssso.      :ssssssso.
+$2      :osssssss/        osssso+++.
+$2     /ossssssss/        +ssssooo/-
+$2   `/ossssso+/:-        -:/+osssso+-
+$2  `+sso+:-`                 `.-/+oso:
+$2 `++:.                           `-/+/
+$2 .`                                 `/"#,
+    },
+    BuiltinLogo {
+        names: &["cachyos"],
+        colors: &["8", "6", "2"],
+        art: r#"
+$1           .$2-------------------------:
+$1          .$3+=========================.
+$1         :$2++===$3++===================-       :$2++$1-
+$1        :$2*++====$3+++++=============-        .$3==:
+$1       -$2*+++=====$3+***++=========:
+$1      =$2*++++========------------:
+$1     =$2*+++++=====-                     $3...
+$1   .$2+*+++++$3=-===:                    .$2=+++=:
+$1  :$2++++$3=====-==:                     -***$3**+
+$1 :$2++=$3=======-=.                      .=+**+$1.
+$1.$2==$3==========.                         .$1.
+$1 :$2+++++++$3====-                $1.$3--==-$1.
+$1  :$2++$3==========.             $1:$2+++++++$1:
+$1   .$2-===$3=========.            $1.$2-=+=-$1.
+$1     :$2+$3=============-------------:
+$1      :$2+$3===============================.
+$1       .$2+$3===============================."#,
+    },
+    BuiltinLogo {
+        names: &["opensuse-tumbleweed", "opensuse-slowroll", "opensuse-leap", "opensuse", "suse"],
+        colors: &["2"],
+        art: r#"
+$1                                     ......
+$1     .,cdxxxoc,.               .:kKMMMNWMMMNk:.
+$1    cKMMN0OOOKWMMXo. ;        ;0MWk:.      .:OMMk.
+$1  ;WMK;.       .lKMMNM,     :NMK,             .OMW.
+$1 cMW;            'WMMMN   ,XMK,                 oMM'
+$1.MMc               ..;l. xMN:                    KM0
+$1'MM.                   'NMO                      oMM
+$1.MM,                 .kMMl                       xMN
+$1 KM0               .kMM0. .dl:,..               .WMd
+$1 .XM0.           ,OMMK,    OMMMMM.            .XMK
+$1   oWMO:.    .;xNMMk,       NNNMKl.          .xWMx
+$1     :ONMMNXMMMKx;          .  ,xNMWKkxllox0NMWk,
+$1         .....                    .:dOOXXKOxl,"#,
+    },
+    BuiltinLogo {
+        names: &["fedora"],
+        colors: &["12", "15"],
+        art: r#"
+$1             .',;::::;,'.
+$1         .';:cccccccccccc:;,.
+$1      .;cccccccccccccccccccccc;.
+$1    .:cccccccccccccccccccccccccc:.
+$1  .;ccccccccccccc;$2.:dddl:.$1;ccccccc;.
+$1 .:ccccccccccccc;$2OWMKOOXMWd$1;ccccccc:.
+$1.:ccccccccccccc;$2KMMc$1;cc;$2xMMc$1;ccccccc:.
+$1,cccccccccccccc;$2MMM.$1;cc;$2;WW:$1;cccccccc,
+$1:cccccccccccccc;$2MMM.$1;cccccccccccccccc:
+$1:ccccccc;$2oxOOOo$1;$2MMM0OOk.$1;cccccccccccc:
+$1cccccc;$20MMKxdd:$1;$2MMMkddc.$1;cccccccccccc;
+$1ccccc;$2XM0'$1;cccc;$2MMM.$1;cccccccccccccccc'
+$1ccccc;$2MMo$1;ccccc;$2MMW.$1;ccccccccccccccc;
+$1ccccc;$20MNc.$1ccc$2.xMMd$1;ccccccccccccccc;
+$1cccccc;$2dNMWXXXWM0:$1;cccccccccccccc:,
+$1cccccccc;$2.:odl:.$1;cccccccccccccc:,.
+$1:cccccccccccccccccccccccccccc:'.
+$1.:cccccccccccccccccccccc:;,..
+$1  '::cccccccccccccc::;,."#,
+    },
+    BuiltinLogo {
+        names: &["ubuntu"],
+        colors: &["1", "15"],
+        art: r#"
+$1            .-/+oossssoo+/-.
+$1        `:+ssssssssssssssssss+:`
+$1      -+ssssssssssssssssssyyssss+-
+$1    .ossssssssssssssssss$2dMMMNy$1sssso.
+$1   /sssssssssss$2hdmmNNmmyNMMMMh$1ssssss/
+$1  +sssssssss$2hmydMMMMMMMNddddy$1ssssssss+
+$1 /ssssssss$2hNMMMyhhyyyyhmNMMMNh$1ssssssss/
+$1.ssssssss$2dMMMNh$1ssssssssss$2hNMMMd$1ssssssss.
+$1+sss$2hhhyNMMNy$1ssssssssssss$2yNMMMy$1sssssss+
+$1oss$2yNMMMNyMMh$1ssssssssssssss$2hmmmh$1ssssssso
+$1oss$2yNMMMNyMMh$1sssssssssssssss$2hmmmh$1sssssso
+$1+sss$2hhhyNMMNy$1ssssssssssss$2yNMMMy$1sssssss+
+$1.ssssssss$2dMMMNh$1ssssssssss$2hNMMMd$1ssssssss.
+$1 /ssssssss$2hNMMMyhhyyyyhdNMMMNh$1ssssssss/
+$1  +sssssssss$2dmydMMMMMMMMddddy$1ssssssss+
+$1   /sssssssssss$2hdmNNNNmyNMMMMh$1ssssss/
+$1    .ossssssssssssssssss$2dMMMNy$1sssso.
+$1      -+sssssssssssssssss$2yyy$1ssss+-
+$1        `:+ssssssssssssssssss+:`
+$1            .-/+oossssoo+/-."#,
+    },
+    BuiltinLogo {
+        names: &["debian"],
+        colors: &["9", "1"],
+        art: r#"
+$2       _,met$$$$$gg.
+$2    ,g$$$$$$$$$$$$$$$P.
+$2  ,g$$P"     """Y$$.".
+$2 ,$$P'              `$$$.
+$2',$$P       ,ggs.     `$$b:
+$2`d$$'     ,$P"'   $1.$2    $$$
+$2 $$P      d$'     $1,$2    $$P
+$2 $$:      $$.   $1-$2    ,d$$'
+$2 $$;      Y$b._   _,d$P'
+$2 Y$$.    $1`.$2`"Y$$$$P"'
+$2 `$$b      $1"-.__
+$2  `Y$$
+$2   `Y$$.
+$2     `$$b.
+$2       `Y$$b.
+$2          `"Y$b._
+$2              `""""#,
+    },
+    BuiltinLogo {
+        names: &["nixos"],
+        colors: &["4", "6"],
+        art: r#"
+$1          ::::.    $2':::::     ::::'
+$1          ':::::    $2':::::.  ::::'
+$1            :::::     $2'::::.:::::
+$1      .......:::::..... $2::::::::
+$1     ::::::::::::::::::. $2::::::    $1::::.
+$1    ::::::::::::::::::::: $2:::::.  $1.::::'
+$2           .....           ::::' $1:::::'
+$2          :::::            '::' $1:::::'
+$2 ........:::::               ' $1:::::::::::.
+$2:::::::::::::                 $1:::::::::::::
+$2 ::::::::::: $1..              $1:::::
+$2     .::::: $1.:::            $1:::::
+$2    .:::::  $1:::::          $1'''''    $2.....
+$2    :::::   $1':::::.  $2......:::::::::::::'
+$2     :::     $1::::::. $2':::::::::::::::::'
+$1            .:::::::: $2'::::::::::
+$1           .::::''::::.     $2'::::.
+$1          .::::'   ::::.     $2'::::.
+$1         .::::      ::::      $2'::::."#,
+    },
+    BuiltinLogo {
+        names: &["linux"],
+        colors: &["15", "8", "11"],
+        art: r#"
+$2        #####
+$2       #######
+$2       ##$1O$2#$1O$2##
+$2       #$3#####$2#
+$2     ##$1##$3###$1##$2##
+$2    #$1##########$2##
+$2   #$1############$2##
+$2   #$1############$2###
+$3  ##$2#$1###########$2##$3#
+$3######$2#$1#######$2#$3######
+$3#######$2#$1#####$2#$3#######
+$3  #####$2#######$3#####"#,
+    },
+];
+
+impl AsciiLogo {
+    /// The built-in logo called `name`, e.g. "arch" or "opensuse-tumbleweed"
+    pub fn builtin(name: &str) -> Option<Self> {
+        let logo = BUILTIN_LOGOS.iter().find(|logo| logo.names.contains(&name))?;
+        Some(Self {
+            lines: logo.art.trim_start_matches('\n').lines().map(str::to_string).collect(),
+            default_colors: logo.colors,
+        })
+    }
+
+    /// The logo of the first of `ids` that has one, the generic Linux logo otherwise
+    pub fn for_os(ids: &[String]) -> Self {
+        ids.iter()
+            .find_map(|id| Self::builtin(id))
+            .or_else(|| Self::builtin("linux"))
+            .expect("the generic Linux logo is built in")
+    }
+
+    /// A user-supplied text logo, using the same `$1`..`$9` placeholders
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read text logo: {}", path.display()))?;
+        Ok(Self {
+            lines: content.lines().map(|line| line.trim_end().to_string()).collect(),
+            default_colors: &[],
+        })
+    }
+
+    /// The logo's lines with every `$n` replaced by `color(n)`, which returns the escape
+    /// sequence switching to that color, and a reset at the end of each line
+    pub fn render(&self, color: impl Fn(usize) -> String) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| {
+                let mut output = String::new();
+                let mut chars = line.chars().peekable();
+                while let Some(c) = chars.next() {
+                    match chars.peek().and_then(|next| next.to_digit(10)) {
+                        Some(index @ 1..=9) if c == '$' => {
+                            chars.next();
+                            output.push_str(&color(index as usize));
+                        }
+                        _ => output.push(c),
+                    }
+                }
+                output.push_str("\x1b[0m");
+                output
+            })
+            .collect()
+    }
+
+    /// Width of the widest line without placeholders, in terminal columns
+    pub fn width(&self) -> usize {
+        self.render(|_| String::new())
+            .iter()
+            .map(|line| unicode_width::UnicodeWidthStr::width(line.trim_end_matches("\x1b[0m")))
+            .max()
+            .unwrap_or(0)
+    }
+}
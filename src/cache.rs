@@ -0,0 +1,326 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::CacheConfig;
+use crate::system_info::{
+    BatteryInfo, DiskEntry, DisplayOutput, GpuInfo, MemoryInfo, ModuleData, PackageCount, SystemInfo,
+    TemperatureSensor, TemperatureSummary,
+};
+
+const CACHE_FILE: &str = "modules.json";
+
+/// A cached module value together with what it was computed from
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    stored_at: u64,
+    context: String,
+    sources: BTreeMap<String, Option<u64>>,
+    value: CachedData,
+}
+
+/// ModuleData as stored in the cache. The untagged form used for the JSON output can't be read
+/// back reliably (an empty list fits every list variant, a record of optional fields fits most
+/// objects), so the variant is stored next to the value here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value")]
+enum CachedData {
+    Text(String),
+    Memory(MemoryInfo),
+    Gpus(Vec<GpuInfo>),
+    Disks(Vec<DiskEntry>),
+    Battery(BatteryInfo),
+    Packages(Vec<PackageCount>),
+    Temperature(Option<f32>),
+    Temperatures(TemperatureSummary),
+    Sensors(Vec<TemperatureSensor>),
+    Displays(Vec<DisplayOutput>),
+}
+
+impl From<ModuleData> for CachedData {
+    fn from(value: ModuleData) -> Self {
+        match value {
+            ModuleData::Text(text) => CachedData::Text(text),
+            ModuleData::Memory(memory) => CachedData::Memory(memory),
+            ModuleData::Gpus(gpus) => CachedData::Gpus(gpus),
+            ModuleData::Disks(disks) => CachedData::Disks(disks),
+            ModuleData::Battery(battery) => CachedData::Battery(battery),
+            ModuleData::Packages(packages) => CachedData::Packages(packages),
+            ModuleData::Temperature(temperature) => CachedData::Temperature(temperature),
+            ModuleData::Temperatures(summary) => CachedData::Temperatures(summary),
+            ModuleData::Sensors(sensors) => CachedData::Sensors(sensors),
+            ModuleData::Displays(displays) => CachedData::Displays(displays),
+        }
+    }
+}
+
+impl From<CachedData> for ModuleData {
+    fn from(value: CachedData) -> Self {
+        match value {
+            CachedData::Text(text) => ModuleData::Text(text),
+            CachedData::Memory(memory) => ModuleData::Memory(memory),
+            CachedData::Gpus(gpus) => ModuleData::Gpus(gpus),
+            CachedData::Disks(disks) => ModuleData::Disks(disks),
+            CachedData::Battery(battery) => ModuleData::Battery(battery),
+            CachedData::Packages(packages) => ModuleData::Packages(packages),
+            CachedData::Temperature(temperature) => ModuleData::Temperature(temperature),
+            CachedData::Temperatures(summary) => ModuleData::Temperatures(summary),
+            CachedData::Sensors(sensors) => ModuleData::Sensors(sensors),
+            CachedData::Displays(displays) => ModuleData::Displays(displays),
+        }
+    }
+}
+
+/// Module results persisted across runs in `$XDG_CACHE_HOME/hyprgreetr/modules.json`.
+/// An entry is reused until its TTL expires, one of the files it depends on changes,
+/// or the environment it was collected in (terminal, boot) differs.
+pub struct ModuleCache {
+    path: Option<PathBuf>,
+    ttl: BTreeMap<String, u64>,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    dirty: AtomicBool,
+}
+
+impl ModuleCache {
+    pub fn load(config: &CacheConfig) -> Self {
+        let path = if config.enabled {
+            dirs::cache_dir().map(|dir| dir.join("hyprgreetr").join(CACHE_FILE))
+        } else {
+            None
+        };
+
+        let entries = path
+            .as_deref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| parse_entries(&content))
+            .unwrap_or_default();
+
+        Self {
+            path,
+            ttl: config.ttl.clone(),
+            entries: Mutex::new(entries),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Cached value for a module, if caching is enabled for it and the entry is still valid
+    pub fn get(&self, key: &str) -> Option<ModuleData> {
+        self.path.as_ref()?;
+        let key = key.to_lowercase();
+        let ttl = *self.ttl.get(&key)?;
+
+        let entries = self.entries.lock().ok()?;
+        let entry = entries.get(&key)?;
+        if now().saturating_sub(entry.stored_at) >= ttl
+            || entry.context != Self::context(&key)
+            || entry.sources != Self::sources(&key)
+        {
+            return None;
+        }
+
+        Some(entry.value.clone().into())
+    }
+
+    /// Remember a freshly collected value for modules that have a TTL configured
+    pub fn store(&self, key: &str, value: &ModuleData) {
+        if self.path.is_none() {
+            return;
+        }
+        let key = key.to_lowercase();
+        if self.ttl.get(&key).is_none_or(|ttl| *ttl == 0) {
+            return;
+        }
+        // Don't pin a failed lookup (e.g. no network yet) for the whole TTL
+        if let ModuleData::Text(text) = value
+            && (text.starts_with("Unknown") || text == "Not available")
+        {
+            return;
+        }
+
+        let entry = CacheEntry {
+            stored_at: now(),
+            context: Self::context(&key),
+            sources: Self::sources(&key),
+            value: value.clone().into(),
+        };
+
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, entry);
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Write the cache back to disk if anything changed during this run
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        }
+
+        let content = {
+            let entries = self.entries.lock().map_err(|_| anyhow::anyhow!("Module cache lock poisoned"))?;
+            serde_json::to_string(&*entries).context("Failed to serialize module cache")?
+        };
+
+        // Write to a temporary file first so concurrently starting shells never read a partial cache
+        let temp_path = path.with_extension(format!("json.{}", std::process::id()));
+        fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write cache file: {}", temp_path.display()))?;
+        fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to replace cache file: {}", path.display()))?;
+
+        Ok(())
+    }
+
+    /// Environment a module's value depends on besides files
+    fn context(key: &str) -> String {
+        match key {
+            // The font is read from the config of whichever terminal we are running in
+            "font" | "terminal" | "terminal_shell_combined" => {
+                env::var("TERM_PROGRAM").or_else(|_| env::var("TERM")).unwrap_or_default()
+            }
+            // Hardware and loaded drivers only change across reboots
+            "gpu_driver" => fs::read_to_string("/proc/sys/kernel/random/boot_id")
+                .map(|id| id.trim().to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    /// Files whose modification time invalidates a module's cached value
+    fn sources(key: &str) -> BTreeMap<String, Option<u64>> {
+        const PACKAGE_DATABASES: &[&str] = &[
+            "/var/lib/pacman/local",
+            "/var/lib/dpkg/status",
+            "/var/lib/rpm",
+            "/usr/lib/sysimage/rpm",
+            "/lib/apk/db/installed",
+            "/var/db/xbps",
+            "/var/db/pkg",
+        ];
+        const FONT_CONFIGS: &[&str] = &[
+            "~/.config/kitty/kitty.conf",
+            "~/.config/ghostty/config",
+            "~/.config/alacritty/alacritty.toml",
+            "~/.config/alacritty/alacritty.yml",
+            "~/.config/Code/User/settings.json",
+        ];
+
+        let expand = |paths: &[&str]| -> Vec<PathBuf> {
+            paths.iter().map(|path| crate::config::expand_tilde(Path::new(path))).collect()
+        };
+
+        // Flatpak's user installation follows $XDG_DATA_HOME, so it is taken from the collector
+        let paths = match key {
+            "packages" => expand(PACKAGE_DATABASES),
+            "flatpak_packages" => SystemInfo::flatpak_app_dirs(),
+            "packages_combined" => [expand(PACKAGE_DATABASES), SystemInfo::flatpak_app_dirs()].concat(),
+            "gpu_driver" => expand(PACKAGE_DATABASES),
+            "os_age" => expand(&["/etc/os-release"]),
+            "font" => expand(FONT_CONFIGS),
+            _ => Vec::new(),
+        };
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let modified = modified_millis(&path);
+                (path.display().to_string(), modified)
+            })
+            .collect()
+    }
+}
+
+/// Decode the cache file entry by entry, so an outdated or damaged entry only loses itself
+fn parse_entries(content: &str) -> HashMap<String, CacheEntry> {
+    serde_json::from_str::<HashMap<String, serde_json::Value>>(content)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(key, entry)| Some((key, serde_json::from_value(entry).ok()?)))
+        .collect()
+}
+
+fn modified_millis(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as u64)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(value: ModuleData) -> CacheEntry {
+        CacheEntry {
+            stored_at: 1,
+            context: String::new(),
+            sources: BTreeMap::new(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn values_keep_their_variant() {
+        // Untagged, all of these would decode as whichever variant happens to come first
+        let mut entries = HashMap::new();
+        entries.insert("gpu".to_string(), entry(ModuleData::Gpus(Vec::new())));
+        entries.insert("disk".to_string(), entry(ModuleData::Disks(Vec::new())));
+        entries.insert("cpu_temp".to_string(), entry(ModuleData::Temperature(None)));
+        entries.insert(
+            "temp_combined".to_string(),
+            entry(ModuleData::Temperatures(TemperatureSummary { cpu_celsius: None, gpu_celsius: None })),
+        );
+        entries.insert("font".to_string(), entry(ModuleData::Text("Iosevka 12".to_string())));
+
+        let content = serde_json::to_string(&entries).unwrap();
+        let decoded: HashMap<String, ModuleData> =
+            parse_entries(&content).into_iter().map(|(key, entry)| (key, entry.value.into())).collect();
+
+        assert_eq!(decoded.len(), 5);
+        assert!(matches!(&decoded["gpu"], ModuleData::Gpus(gpus) if gpus.is_empty()));
+        assert!(matches!(&decoded["disk"], ModuleData::Disks(disks) if disks.is_empty()));
+        assert!(matches!(decoded["cpu_temp"], ModuleData::Temperature(None)));
+        assert!(matches!(
+            decoded["temp_combined"],
+            ModuleData::Temperatures(TemperatureSummary { cpu_celsius: None, gpu_celsius: None })
+        ));
+        assert!(matches!(&decoded["font"], ModuleData::Text(text) if text == "Iosevka 12"));
+    }
+
+    #[test]
+    fn damaged_entries_are_dropped_alone() {
+        let content = r#"{
+            "font": {"stored_at": 1, "context": "", "sources": {}, "value": {"kind": "Text", "value": "Iosevka 12"}},
+            "packages": {"stored_at": 1, "context": "", "sources": {}, "value": [{"manager": "pacman", "count": 1}]},
+            "os_age": {"stored_at": 1}
+        }"#;
+
+        let entries = parse_entries(content);
+        assert_eq!(entries.len(), 1);
+        assert!(entries.contains_key("font"));
+    }
+
+    #[test]
+    fn unreadable_file_gives_empty_cache() {
+        assert!(parse_entries("not json").is_empty());
+    }
+}
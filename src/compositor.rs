@@ -0,0 +1,125 @@
+use serde_json::Value;
+use std::env;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long to wait on a compositor socket before giving up
+const IPC_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// i3/Sway IPC message types
+pub const I3_GET_OUTPUTS: u32 = 3;
+const I3_GET_VERSION: u32 = 7;
+
+/// A running compositor found through its IPC socket
+pub struct Compositor {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// Detect the compositor from the environment variables and sockets it exports
+pub fn detect() -> Option<Compositor> {
+    if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        let version = hyprland_request("j/version")
+            .and_then(|reply| serde_json::from_str::<Value>(&reply).ok())
+            .and_then(|reply| {
+                let tag = reply["version"].as_str().or_else(|| reply["tag"].as_str())?;
+                Some(tag.trim_start_matches('v').to_string())
+            });
+        return Some(Compositor { name: "Hyprland".to_string(), version });
+    }
+
+    if env::var_os("NIRI_SOCKET").is_some() {
+        let version = niri_request("\"Version\"")
+            .and_then(|reply| reply["Ok"]["Version"].as_str().map(str::to_string));
+        return Some(Compositor { name: "Niri".to_string(), version });
+    }
+
+    for (variable, name) in [("SWAYSOCK", "Sway"), ("I3SOCK", "i3")] {
+        if env::var_os(variable).is_some() {
+            let version = i3_request(I3_GET_VERSION, "")
+                .and_then(|reply| reply["human_readable"].as_str().map(str::to_string));
+            return Some(Compositor { name: name.to_string(), version });
+        }
+    }
+
+    None
+}
+
+/// Name of the running compositor from its environment variables alone, without talking to it
+pub fn detect_name() -> Option<&'static str> {
+    [
+        ("HYPRLAND_INSTANCE_SIGNATURE", "Hyprland"),
+        ("NIRI_SOCKET", "Niri"),
+        ("SWAYSOCK", "Sway"),
+        ("I3SOCK", "i3"),
+    ]
+    .into_iter()
+    .find(|(variable, _)| env::var_os(variable).is_some())
+    .map(|(_, name)| name)
+}
+
+/// Send a request to Hyprland's command socket (e.g. "j/monitors") and return the raw reply
+pub fn hyprland_request(command: &str) -> Option<String> {
+    let signature = env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+
+    // Hyprland >= 0.40 keeps its sockets in $XDG_RUNTIME_DIR, older versions in /tmp
+    let mut candidates = Vec::new();
+    if let Ok(runtime_dir) = env::var("XDG_RUNTIME_DIR") {
+        candidates.push(PathBuf::from(runtime_dir).join("hypr").join(&signature).join(".socket.sock"));
+    }
+    candidates.push(PathBuf::from("/tmp/hypr").join(&signature).join(".socket.sock"));
+
+    let mut stream = candidates.iter().find_map(|path| connect(path))?;
+    stream.write_all(command.as_bytes()).ok()?;
+
+    // Hyprland closes the connection after replying
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).ok()?;
+    Some(reply)
+}
+
+/// Send a single-line JSON request (e.g. "\"Outputs\"") to niri and parse its reply
+pub fn niri_request(request: &str) -> Option<Value> {
+    let socket = env::var_os("NIRI_SOCKET")?;
+    let mut stream = connect(Path::new(&socket))?;
+    stream.write_all(format!("{}\n", request).as_bytes()).ok()?;
+    stream.shutdown(std::net::Shutdown::Write).ok()?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).ok()?;
+    serde_json::from_str(reply.lines().next()?).ok()
+}
+
+/// Send a message over the i3/Sway IPC protocol ("i3-ipc", payload length, type, payload)
+/// and parse the JSON reply
+pub fn i3_request(message_type: u32, payload: &str) -> Option<Value> {
+    let socket = env::var_os("SWAYSOCK").or_else(|| env::var_os("I3SOCK"))?;
+    let mut stream = connect(Path::new(&socket))?;
+
+    let mut message = b"i3-ipc".to_vec();
+    message.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+    message.extend_from_slice(&message_type.to_ne_bytes());
+    message.extend_from_slice(payload.as_bytes());
+    stream.write_all(&message).ok()?;
+
+    // The reply has the same 14-byte header
+    let mut header = [0u8; 14];
+    stream.read_exact(&mut header).ok()?;
+    if &header[..6] != b"i3-ipc" {
+        return None;
+    }
+    let length = u32::from_ne_bytes(header[6..10].try_into().ok()?) as usize;
+
+    let mut reply = vec![0u8; length];
+    stream.read_exact(&mut reply).ok()?;
+    serde_json::from_slice(&reply).ok()
+}
+
+fn connect(path: &Path) -> Option<UnixStream> {
+    let stream = UnixStream::connect(path).ok()?;
+    stream.set_read_timeout(Some(IPC_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(IPC_TIMEOUT)).ok()?;
+    Some(stream)
+}
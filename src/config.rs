@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -24,6 +25,8 @@ pub struct Config {
     pub general: GeneralConfig,
     pub display: DisplayConfig,
     pub modules: ModulesConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
     pub show_motd: bool,
     pub motd_file: PathBuf,
 }
@@ -56,34 +59,158 @@ pub struct ColorsConfig {
 pub struct DisplayConfig {
     pub show_image: bool,
     pub image_path: Option<PathBuf>,
+    #[serde(default = "default_image_rotation")]
+    pub image_rotation: String, // "random", "round-robin" or "schedule" when several logos match
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub image_schedule: Vec<ImageScheduleEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub logo_map: BTreeMap<String, LogoPaths>, // OS ID or compositor name -> logo(s), used when image_path matches nothing
     pub image_size: ImageSize,
-    pub prefer_kitty_graphics: bool,
+    /// Deprecated: `false` behaves like `graphics_protocol = "blocks"` when the protocol is "auto"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefer_kitty_graphics: Option<bool>,
+    #[serde(default = "default_graphics_protocol")]
+    pub graphics_protocol: String, // "auto", "kitty", "sixel", "iterm2", "blocks"
     pub padding: u8,
     pub show_border: bool,
     pub border_top: String,
     pub border_bottom: String,
     pub border_color: String,
     pub block_rendering: BlockRenderingConfig,
+    #[serde(default)]
+    pub animation: AnimationConfig,
+    #[serde(default)]
+    pub ascii_logo: AsciiLogoConfig,
+}
+
+/// Values `graphics_protocol` accepts
+const GRAPHICS_PROTOCOLS: &[&str] = &["auto", "kitty", "sixel", "iterm2", "blocks"];
+
+impl DisplayConfig {
+    /// Warn about a `graphics_protocol` that isn't known, which is treated like "auto"
+    fn validate_graphics_protocol(&self) {
+        if !GRAPHICS_PROTOCOLS.contains(&self.graphics_protocol.as_str()) {
+            eprintln!(
+                "[Warning] Unknown graphics_protocol \"{}\", using \"auto\" (expected one of: {})",
+                self.graphics_protocol,
+                GRAPHICS_PROTOCOLS.join(", ")
+            );
+        }
+    }
+}
+
+/// One logo, or several to rotate through, for a `logo_map` key
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum LogoPaths {
+    One(PathBuf),
+    Many(Vec<PathBuf>),
+}
+
+impl LogoPaths {
+    pub fn paths(&self) -> &[PathBuf] {
+        match self {
+            LogoPaths::One(path) => std::slice::from_ref(path),
+            LogoPaths::Many(paths) => paths,
+        }
+    }
+}
+
+/// Logos for certain days and times, used with `image_rotation = "schedule"`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ImageScheduleEntry {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<String>,    // "mon", "tue", ... "sun"; every day when empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>, // "HH:MM", from midnight when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,   // "HH:MM" (exclusive), until midnight when not set; may wrap past midnight
+    pub image: String,        // File name or glob pattern (e.g. "night_*") among the matching logos
+}
+
+/// Playback of animated logos (GIF, APNG, WebP)
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AnimationConfig {
+    pub enabled: bool,        // Animate logos with more than one frame; otherwise the first frame is shown
+    pub loops: u32,           // How often to play the animation before stopping on the last frame (0 = forever in kitty)
+    pub max_duration_ms: u64, // Block rendering blocks the prompt while playing, so it stops after this long
+}
+
+impl Default for AnimationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            loops: 1,
+            max_duration_ms: 1500,
+        }
+    }
+}
+
+/// Text logo shown beside the info when no image is displayed
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AsciiLogoConfig {
+    pub enabled: bool,       // Show a text logo when the image wasn't found
+    pub logo: String,        // "auto" (from /etc/os-release ID/ID_LIKE), a built-in name or a path to a text file
+    pub colors: Vec<String>, // Colors for $1..$9: "#RRGGBB" or ANSI color numbers 0-255; missing ones use the logo's own
+}
+
+impl Default for AsciiLogoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            logo: "auto".to_string(),
+            colors: Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct BlockRenderingConfig {
-    pub block_style: String,           // "default", "ascii", "braille", "custom"
+    pub block_style: String,           // "default", "ascii", "custom", "halfblock", "quadrant", "sextant", "braille"
     pub custom_blocks: Vec<String>,    // Custom block characters (used when block_style = "custom")
     pub brightness_thresholds: Vec<f32>, // Brightness thresholds for block selection (0.0-1.0)
     pub color_mode: String,            // "truecolor", "256color", "16color", "monochrome"
     pub contrast: f32,                 // Contrast adjustment (0.5-2.0)
     pub brightness_boost: f32,         // Brightness boost (-0.5 to +0.5)
     pub sampling_method: String,       // "average", "dominant", "weighted"
-    pub enable_dithering: bool,        // Enable dithering for better quality
+    #[serde(default = "default_background_color")]
+    pub background_color: String,      // "auto" (asked from the terminal) or "#RRGGBB", for blending transparency
+    #[serde(default)]
+    pub dithering: Option<String>,     // "none", "floyd", "atkinson", "bayer4"
+    /// Deprecated: `true` means `dithering = "floyd"` when `dithering` isn't set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_dithering: Option<bool>,
+}
+
+impl BlockRenderingConfig {
+    /// Dithering method to use, honoring the deprecated enable_dithering flag
+    pub fn dithering_method(&self) -> &str {
+        match (&self.dithering, self.enable_dithering) {
+            (Some(method), _) => method,
+            (None, Some(true)) => "floyd",
+            _ => "none",
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ImageSize {
     pub width: u32,
     pub height: u32,
-    pub cell_width: u32,  // Pixel pro Terminal-Zeichen (Breite)
-    pub cell_height: u32, // Pixel pro Terminal-Zeichen (Höhe)
+    #[serde(default = "default_cell_width")]
+    pub cell_width: u32,  // Pixel pro Terminal-Zeichen (Breite), falls das Terminal sie nicht meldet
+    #[serde(default = "default_cell_height")]
+    pub cell_height: u32, // Pixel pro Terminal-Zeichen (Höhe), falls das Terminal sie nicht meldet
+}
+
+fn default_cell_width() -> u32 {
+    10
+}
+
+fn default_cell_height() -> u32 {
+    20
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -116,6 +243,12 @@ pub struct ModulesConfig {
     pub gpu: bool,
     pub gpu_temp: bool,
     pub temp_combined: bool,
+    #[serde(default)]
+    pub sensors: bool,
+    /// Temperature sensors shown by the sensors module: roles ("cpu", "nvme", ...),
+    /// hwmon chip names ("k10temp") or "chip/label" ("nvme/Composite")
+    #[serde(default = "default_temperature_sensors")]
+    pub temperature_sensors: Vec<String>,
     pub gpu_driver: bool,
     pub memory: bool,
     pub disk: bool,
@@ -123,6 +256,241 @@ pub struct ModulesConfig {
     pub battery: bool,
     pub locale: bool,
     pub display_names: ModuleDisplayConfig,
+    #[serde(default)]
+    pub custom: Vec<CustomModuleConfig>,
+    /// Display order: module keys, "---" (separator), "" (blank line) or "# Title" (section header)
+    #[serde(default)]
+    pub order: Vec<String>,
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
+}
+
+/// Time limits for the external commands modules run, so a hung command can never block shell startup
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    pub module_ms: u64,                 // Default time limit for a single module
+    pub total_ms: u64,                  // Time budget for collecting all modules
+    pub show_timed_out: bool,           // Show "timed out" for unfinished modules instead of omitting them
+    pub modules: BTreeMap<String, u64>, // Per-module overrides, e.g. public_ip = 3000
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            module_ms: 1000,
+            total_ms: 2000,
+            show_timed_out: true,
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+/// Persistent cache for modules that rarely change between shell launches
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    pub ttl: BTreeMap<String, u64>, // Seconds to keep each module's result; unlisted modules are never cached
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        let ttl = [
+            ("packages", 3600),
+            ("flatpak_packages", 3600),
+            ("packages_combined", 3600),
+            ("gpu_driver", 86400),
+            ("os_age", 3600),
+            ("font", 86400),
+            ("public_ip", 600),
+        ];
+        
+        Self {
+            enabled: true,
+            ttl: ttl.into_iter().map(|(key, seconds)| (key.to_string(), seconds)).collect(),
+        }
+    }
+}
+
+/// Defines `MODULES` and the lookups by module key on `ModulesConfig` and `ModuleDisplayConfig`
+/// from a single list, so a new module only has to be added in one place. `=> field` names the
+/// `display_names` entry for modules that share one with another module.
+macro_rules! builtin_modules {
+    ($($key:ident: $name:literal $(=> $display_name:ident)?,)*) => {
+        /// All built-in modules as (key, default display name), in default display order
+        pub const MODULES: &[(&str, &str)] = &[$((stringify!($key), $name)),*];
+
+        impl ModulesConfig {
+            /// Whether the built-in module with the given key is enabled
+            pub fn is_enabled(&self, key: &str) -> bool {
+                match key {
+                    $(stringify!($key) => self.$key,)*
+                    _ => false,
+                }
+            }
+        }
+
+        impl ModuleDisplayConfig {
+            /// Custom display name configured for the built-in module with the given key
+            pub fn get(&self, key: &str) -> Option<&String> {
+                match key {
+                    $(stringify!($key) => builtin_modules!(@display_name self, $key $(, $display_name)?).as_ref(),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+    (@display_name $names:ident, $key:ident) => { $names.$key };
+    (@display_name $names:ident, $key:ident, $display_name:ident) => { $names.$display_name };
+}
+
+builtin_modules! {
+    user_at_host: "Login",
+    user: "User",
+    hostname: "Hostname",
+    os: "OS",
+    kernel: "Kernel",
+    linux: "Linux",
+    uptime: "Uptime",
+    os_age: "OS Age",
+    packages: "Packages",
+    flatpak_packages: "Flatpak",
+    packages_combined: "Packages" => packages,
+    shell: "Shell",
+    terminal: "Terminal",
+    terminal_shell_combined: "Terminal & Shell",
+    resolution: "Resolution",
+    de: "DE",
+    wm: "WM",
+    theme: "Theme",
+    icons: "Icons",
+    font: "Font",
+    locale: "Locale",
+    cpu: "CPU",
+    cpu_temp: "CPU Temp",
+    gpu: "GPU",
+    gpu_temp: "GPU Temp",
+    gpu_driver: "GPU Driver",
+    temp_combined: "Temperatures",
+    sensors: "Sensors",
+    memory: "Memory",
+    battery: "Battery",
+    network: "Network",
+    public_ip: "Public IP",
+    disk: "Disk",
+    dysk: "Drives",
+}
+
+/// A single entry of `modules.order`
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderEntry {
+    Module(String),
+    Separator,
+    Blank,
+    Header(String),
+}
+
+impl OrderEntry {
+    pub fn parse(entry: &str) -> Self {
+        let trimmed = entry.trim();
+        if trimmed.is_empty() {
+            OrderEntry::Blank
+        } else if trimmed == "---" {
+            OrderEntry::Separator
+        } else if let Some(title) = trimmed.strip_prefix('#') {
+            OrderEntry::Header(title.trim().to_string())
+        } else {
+            OrderEntry::Module(trimmed.to_lowercase())
+        }
+    }
+}
+
+impl ModulesConfig {
+    /// Whether a module has to be collected: it is enabled and, when `order` is set, listed there
+    pub fn should_collect(&self, key: &str) -> bool {
+        let key = key.to_lowercase();
+        let enabled = self.is_enabled(&key) || self.custom.iter().any(|custom| custom.key() == key);
+        enabled
+            && (self.order.is_empty()
+                || self.order.iter().any(|entry| OrderEntry::parse(entry) == OrderEntry::Module(key.clone())))
+    }
+
+    /// Display layout: the configured `order`, or every module in default order
+    pub fn layout(&self) -> Vec<OrderEntry> {
+        if self.order.is_empty() {
+            MODULES.iter()
+                .map(|(key, _)| OrderEntry::Module(key.to_string()))
+                .chain(self.custom.iter().map(|custom| OrderEntry::Module(custom.key())))
+                .collect()
+        } else {
+            self.order.iter().map(|entry| OrderEntry::parse(entry)).collect()
+        }
+    }
+
+    /// Check that every module referenced in `order` exists
+    fn validate_order(&self) -> Result<()> {
+        let unknown: Vec<String> = self.order.iter()
+            .filter_map(|entry| match OrderEntry::parse(entry) {
+                OrderEntry::Module(key) => Some(key),
+                _ => None,
+            })
+            .filter(|key| {
+                !MODULES.iter().any(|(known, _)| known == key) &&
+                !self.custom.iter().any(|custom| &custom.key() == key)
+            })
+            .collect();
+        
+        if !unknown.is_empty() {
+            anyhow::bail!(
+                "Unknown module(s) in modules.order: {} (custom modules are referenced as \"custom_<name>\")",
+                unknown.join(", ")
+            );
+        }
+        
+        Ok(())
+    }
+}
+
+/// A user-defined module whose value is the output of a shell command
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CustomModuleConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default = "default_custom_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_background_color() -> String {
+    "auto".to_string()
+}
+
+fn default_graphics_protocol() -> String {
+    "auto".to_string()
+}
+
+fn default_image_rotation() -> String {
+    "random".to_string()
+}
+
+fn default_custom_timeout_ms() -> u64 {
+    1000
+}
+
+fn default_temperature_sensors() -> Vec<String> {
+    vec!["cpu".to_string(), "gpu".to_string(), "nvme".to_string()]
+}
+
+impl CustomModuleConfig {
+    /// Key used for the gathered data, e.g. "Kernel updates" -> "custom_kernel_updates"
+    pub fn key(&self) -> String {
+        let mut key = String::from("custom");
+        for word in self.name.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+            key.push('_');
+            key.push_str(&word.to_lowercase());
+        }
+        key
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -134,6 +502,7 @@ pub struct ModuleDisplayConfig {
     pub uptime: Option<String>,
     pub os_age: Option<String>,
     pub packages: Option<String>,
+    pub flatpak_packages: Option<String>,
     pub shell: Option<String>,
     pub resolution: Option<String>,
     pub network: Option<String>,
@@ -152,6 +521,7 @@ pub struct ModuleDisplayConfig {
     pub gpu: Option<String>,
     pub gpu_temp: Option<String>,
     pub temp_combined: Option<String>,
+    pub sensors: Option<String>,
     pub gpu_driver: Option<String>,
     pub memory: Option<String>,
     pub disk: Option<String>,
@@ -190,13 +560,17 @@ impl Default for Config {
             display: DisplayConfig {
                 show_image: true,
                 image_path: None,
+                image_rotation: default_image_rotation(),
+                image_schedule: Vec::new(),
+                logo_map: BTreeMap::new(),
                 image_size: ImageSize {
                     width: 40,
                     height: 20,
-                    cell_width: 10,  // Standard-Schätzung für moderne Terminals
-                    cell_height: 20, // Kann per config angepasst werden
+                    cell_width: default_cell_width(),   // Standard-Schätzung für moderne Terminals
+                    cell_height: default_cell_height(), // Kann per config angepasst werden
                 },
-                prefer_kitty_graphics: true,
+                prefer_kitty_graphics: None,
+                graphics_protocol: default_graphics_protocol(),
                 padding: 2,
                 show_border: false,
                 border_top: "┌──────────────────────────────────────────────┐".to_string(),
@@ -210,8 +584,12 @@ impl Default for Config {
                     contrast: 1.0,
                     brightness_boost: 0.0,
                     sampling_method: "average".to_string(),
-                    enable_dithering: false,
+                    background_color: default_background_color(),
+                    dithering: None,
+                    enable_dithering: None,
                 },
+                animation: AnimationConfig::default(),
+                ascii_logo: AsciiLogoConfig::default(),
             },
             modules: ModulesConfig {
                 show_versions: true,
@@ -242,6 +620,8 @@ impl Default for Config {
                 gpu: true,
                 gpu_temp: true,
                 temp_combined: false,
+                sensors: false,
+                temperature_sensors: default_temperature_sensors(),
                 gpu_driver: true,
                 memory: true,
                 disk: true,
@@ -256,6 +636,7 @@ impl Default for Config {
                     uptime: None,
                     os_age: None,
                     packages: None,
+                    flatpak_packages: None,
                     shell: None,
                     resolution: None,
                     network: None,
@@ -274,6 +655,7 @@ impl Default for Config {
                     gpu: None,
                     gpu_temp: None,
                     temp_combined: None,
+                    sensors: None,
                     gpu_driver: None,
                     memory: None,
                     disk: None,
@@ -281,7 +663,11 @@ impl Default for Config {
                     battery: None,
                     locale: None,
                 },
+                custom: Vec::new(),
+                order: Vec::new(),
+                timeouts: TimeoutConfig::default(),
             },
+            cache: CacheConfig::default(),
             show_motd: true,
             motd_file: dirs::config_dir()
                 .unwrap_or_else(|| PathBuf::from(".config"))
@@ -363,7 +749,7 @@ impl Config {
                 }
                 
                 if png_count > 0 {
-                    println!("Copied {} PNG files from assets to config directory", png_count);
+                    eprintln!("Copied {} PNG files from assets to config directory", png_count);
                 }
                 
                 assets_found = true;
@@ -411,7 +797,7 @@ impl Config {
                         .with_context(|| format!("Failed to copy example config from {} to {}", 
                                                 example_config.display(), path.display()))?;
                     
-                    println!("Copied example configuration from {}", example_config.display());
+                    eprintln!("Copied example configuration from {}", example_config.display());
                     config_copied = true;
                     break;
                 }
@@ -421,7 +807,7 @@ impl Config {
             if !config_copied {
                 let config = Self::default();
                 config.save(path)?;
-                println!("Created default configuration (examples not found)");
+                eprintln!("Created default configuration (examples not found)");
             }
             
             // Setup default assets (pngs directory and logo)
@@ -437,6 +823,9 @@ impl Config {
             
             let config: Config = toml::from_str(&content)
                 .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+            config.modules.validate_order()
+                .with_context(|| format!("Invalid config file: {}", path.display()))?;
+            config.display.validate_graphics_protocol();
             
             return Ok(config);
         }
@@ -446,6 +835,9 @@ impl Config {
         
         let config: Config = toml::from_str(&content)
             .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        config.modules.validate_order()
+            .with_context(|| format!("Invalid config file: {}", path.display()))?;
+        config.display.validate_graphics_protocol();
         
         Ok(config)
     }
@@ -493,7 +885,7 @@ impl MotdConfig {
                             }
                             
                             if fs::copy(&example_motd, path).is_ok() {
-                                println!("Copied example MOTD configuration from {}", example_motd.display());
+                                eprintln!("Copied example MOTD configuration from {}", example_motd.display());
                                 motd_copied = true;
                                 break;
                             }
@@ -542,3 +934,73 @@ impl MotdConfig {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_module(name: &str) -> CustomModuleConfig {
+        CustomModuleConfig {
+            name: name.to_string(),
+            command: "true".to_string(),
+            timeout_ms: default_custom_timeout_ms(),
+        }
+    }
+
+    #[test]
+    fn custom_module_key_joins_lowercase_words() {
+        assert_eq!(custom_module("Kernel updates").key(), "custom_kernel_updates");
+        assert_eq!(custom_module("  Git: status!  ").key(), "custom_git_status");
+        assert_eq!(custom_module("CPU-Governor").key(), "custom_cpu_governor");
+    }
+
+    #[test]
+    fn custom_module_key_without_words() {
+        assert_eq!(custom_module("").key(), "custom");
+        assert_eq!(custom_module("--").key(), "custom");
+    }
+
+    fn modules_with_order(order: &[&str]) -> ModulesConfig {
+        let mut modules = Config::default().modules;
+        modules.order = order.iter().map(|entry| entry.to_string()).collect();
+        modules.custom = vec![custom_module("Kernel updates")];
+        modules
+    }
+
+    #[test]
+    fn order_entries_are_parsed() {
+        assert_eq!(OrderEntry::parse(" CPU "), OrderEntry::Module("cpu".to_string()));
+        assert_eq!(OrderEntry::parse("---"), OrderEntry::Separator);
+        assert_eq!(OrderEntry::parse("  "), OrderEntry::Blank);
+        assert_eq!(OrderEntry::parse("# Hardware "), OrderEntry::Header("Hardware".to_string()));
+    }
+
+    #[test]
+    fn validate_order_accepts_known_entries() {
+        let modules = modules_with_order(&["# System", "os", "---", "", "CPU", "custom_kernel_updates"]);
+        assert!(modules.validate_order().is_ok());
+    }
+
+    #[test]
+    fn validate_order_reports_unknown_modules() {
+        let modules = modules_with_order(&["os", "memroy", "kernel_updates"]);
+        let error = modules.validate_order().unwrap_err().to_string();
+        assert!(error.contains("memroy, kernel_updates"), "{}", error);
+    }
+
+    #[test]
+    fn should_collect_only_listed_modules() {
+        let mut modules = modules_with_order(&["os", "custom_kernel_updates"]);
+        modules.os = true;
+        modules.kernel = true;
+        assert!(modules.should_collect("OS"));
+        assert!(modules.should_collect("custom_kernel_updates"));
+        assert!(!modules.should_collect("kernel"));
+
+        modules.os = false;
+        assert!(!modules.should_collect("os"));
+
+        modules.order.clear();
+        assert!(modules.should_collect("kernel"));
+    }
+}
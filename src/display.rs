@@ -2,22 +2,84 @@ use anyhow::Result;
 use colored::{Colorize, ColoredString};
 use image;
 use unicode_width::UnicodeWidthStr;
-use crate::config::{Config, MotdConfig, expand_tilde};
-use crate::system_info::SystemInfo;
+use crate::config::{Config, MotdConfig, OrderEntry, MODULES, expand_tilde};
+use crate::system_info::{DiskEntry, ModuleData, SystemInfo, TemperatureSensor};
 use crate::kitty_graphics::KittyGraphics;
+use crate::sixel::SixelGraphics;
+use crate::iterm2::Iterm2Graphics;
+use crate::dither::{DitherMethod, Ditherer};
+use crate::animation::AnimationFrame;
+use crate::ascii_logo::AsciiLogo;
+
+/// An RGB color of a rendered pixel
+type Rgb = (u8, u8, u8);
+
+/// Pixels with less alpha than this are left out of block-rendered images entirely
+const TRANSPARENT_ALPHA: u8 = 16;
+
+/// Pixel-accurate image protocols; everything else falls back to block rendering
+#[derive(Clone, Copy)]
+enum GraphicsBackend {
+    Kitty,
+    Sixel,
+    Iterm2,
+}
 
 pub struct Display {
     config: Config,
     show_images: bool,
     kitty_graphics: KittyGraphics,
+    sixel_graphics: SixelGraphics,
+    iterm2_graphics: Iterm2Graphics,
+    /// Pixel size of one character cell, as reported by the terminal or configured
+    cell_size: (u32, u32),
+    /// Color semi-transparent pixels are blended with, resolved on first use
+    background: std::cell::OnceCell<Rgb>,
 }
 
 impl Display {
     pub fn new(config: Config, show_images: bool) -> Self {
+        let mut kitty_graphics = KittyGraphics::new();
+        let mut sixel_graphics = SixelGraphics::new();
+        let mut iterm2_graphics = Iterm2Graphics::new();
+
+        // Ask the terminal itself when picking a protocol automatically; the environment
+        // variables the constructors look at are wrong over SSH and inside tmux
+        let wants_image = show_images && config.display.show_image;
+        if wants_image && config.display.graphics_protocol == "auto"
+            && let Some(capabilities) = crate::terminal::probe(config.cache.enabled)
+        {
+            kitty_graphics.supports_kitty = capabilities.kitty_graphics;
+            sixel_graphics.supports_sixel = capabilities.sixel;
+            if let Some(iterm2) = capabilities.iterm2() {
+                iterm2_graphics.supports_iterm2 = iterm2;
+            }
+        }
+
+        // A protocol chosen in the config overrides terminal detection
+        match config.display.graphics_protocol.as_str() {
+            "kitty" => kitty_graphics.supports_kitty = true,
+            "sixel" => sixel_graphics.supports_sixel = true,
+            "iterm2" => iterm2_graphics.supports_iterm2 = true,
+            _ => {}
+        }
+
+        // The configured cell size is only a fallback for terminals that don't report theirs
+        let configured_cell_size = (config.display.image_size.cell_width, config.display.image_size.cell_height);
+        let cell_size = if wants_image {
+            crate::terminal::cell_size().unwrap_or(configured_cell_size)
+        } else {
+            configured_cell_size
+        };
+
         Self {
             config,
             show_images,
-            kitty_graphics: KittyGraphics::new(),
+            kitty_graphics,
+            sixel_graphics,
+            iterm2_graphics,
+            cell_size,
+            background: std::cell::OnceCell::new(),
         }
     }
 
@@ -35,25 +97,8 @@ impl Display {
         
         // Display PNG image if configured and available
         if self.show_images && self.config.display.show_image {
-            let mut image_to_use: Option<std::path::PathBuf> = None;
-            
-            // First, check if a specific image path is configured
-            if let Some(ref image_path) = self.config.display.image_path {
-                let expanded_path = expand_tilde(image_path);
-                if expanded_path.exists() {
-                    image_to_use = Some(expanded_path);
-                }
-            }
-            
-            // If no specific image or it doesn't exist, try default logo
-            if image_to_use.is_none() {
-                if let Some(config_dir) = dirs::config_dir() {
-                    let default_logo = config_dir.join("hyprgreetr").join("pngs").join("hyprland_logo.png");
-                    if default_logo.exists() {
-                        image_to_use = Some(default_logo);
-                    }
-                }
-            }
+            // The configured image, or a logo matching the OS or compositor
+            let image_to_use = crate::logo::resolve(&self.config.display);
             
             // Display the image if we found one
             if let Some(image_path) = image_to_use {
@@ -62,98 +107,519 @@ impl Display {
                 self.show_motd_if_enabled()?;
                 return Ok(());
             }
+            
+            // An image was wanted but none was found - show a text logo instead
+            if let Some(logo) = self.ascii_logo() {
+                self.show_horizontal_layout_with_text_logo(&logo, &info_lines);
+                self.show_motd_if_enabled()?;
+                return Ok(());
+            }
         }
         
-        // No image configured or available - show info only
+        // Images disabled, or no logo available - show info only
         self.show_info_only(&info_lines);
         self.show_motd_if_enabled()?;
         
         Ok(())
     }
 
-    fn is_kitty_terminal(&self) -> bool {
-        // Check if we're in a terminal that supports Kitty graphics protocol
-        std::env::var("TERM").unwrap_or_default().contains("kitty") ||
-        std::env::var("TERM_PROGRAM").unwrap_or_default().contains("ghostty") ||
-        std::env::var("TERM_PROGRAM").unwrap_or_default().contains("iTerm")
+    /// Pick the graphics protocol to draw the image with, None means block rendering
+    fn graphics_backend(&self) -> Option<GraphicsBackend> {
+        match self.config.display.graphics_protocol.as_str() {
+            "kitty" => Some(GraphicsBackend::Kitty),
+            "sixel" => Some(GraphicsBackend::Sixel),
+            "iterm2" => Some(GraphicsBackend::Iterm2),
+            "blocks" => None,
+            // Legacy configs turned graphics off with prefer_kitty_graphics = false
+            _ if self.config.display.prefer_kitty_graphics == Some(false) => None,
+            _ if self.kitty_graphics.supports_kitty => Some(GraphicsBackend::Kitty),
+            _ if self.iterm2_graphics.supports_iterm2 => Some(GraphicsBackend::Iterm2),
+            _ if self.sixel_graphics.supports_sixel => Some(GraphicsBackend::Sixel),
+            _ => None,
+        }
     }
     
     fn render_image_as_text_blocks(&self, image_path: &std::path::PathBuf) -> Result<Vec<String>> {
-        // Calculate effective dimensions with cell size adjustments (same as render_image_to_terminal)
+        // Try to load and process the image
+        let lines = if crate::svg::is_svg(image_path) {
+            self.render_svg_as_text_blocks(image_path).ok()
+        } else {
+            image::open(image_path).ok().map(|img| self.render_frame_as_text_blocks(&img))
+        };
+        
+        match lines {
+            Some(lines) => Ok(lines),
+            None => {
+                // If image loading fails, create placeholder lines
+                let width = self.config.display.image_size.width as usize;
+                let height = self.config.display.image_size.height as usize;
+                Ok(vec![" ".repeat(width); height])
+            }
+        }
+    }
+    
+    fn render_frame_as_text_blocks(&self, img: &image::DynamicImage) -> Vec<String> {
         let width = self.config.display.image_size.width;
         let height = self.config.display.image_size.height;
-        let cell_width = self.config.display.image_size.cell_width;
-        let cell_height = self.config.display.image_size.cell_height;
         
-        let effective_width = if cell_width > 20 { width / 2 } else { width };
-        let effective_height = if cell_height < 15 { height / 2 } else { height };
+        // Fit the image into width x height cells, taking the cell shape into account
+        // so the logo keeps its aspect ratio
+        let (effective_width, effective_height) =
+            crate::terminal::fit_cells(img.width(), img.height(), width, height, self.cell_size);
+        
+        self.render_cells(img, effective_width, effective_height)
+    }
+    
+    /// Rasterize an SVG logo at exactly the pixel grid the block style samples, so it stays
+    /// sharp instead of being scaled from a fixed-size bitmap
+    fn render_svg_as_text_blocks(&self, image_path: &std::path::Path) -> Result<Vec<String>> {
+        let width = self.config.display.image_size.width;
+        let height = self.config.display.image_size.height;
         
-        // Get block rendering configuration
+        let (svg_width, svg_height) = crate::svg::dimensions(image_path)?;
+        let (effective_width, effective_height) =
+            crate::terminal::fit_cells(svg_width, svg_height, width, height, self.cell_size);
+        let (columns, rows) = Self::cell_pixels(&self.config.display.block_rendering.block_style);
+        let raster = crate::svg::rasterize(image_path, effective_width * columns, effective_height * rows)?;
+        
+        Ok(self.render_cells(&image::DynamicImage::ImageRgba8(raster), effective_width, effective_height))
+    }
+    
+    /// Image pixels sampled per cell by each block style
+    fn cell_pixels(block_style: &str) -> (u32, u32) {
+        match block_style {
+            "halfblock" => (1, 2),
+            "quadrant" => (2, 2),
+            "sextant" => (2, 3),
+            "braille" => (2, 4),
+            _ => (2, 1),
+        }
+    }
+    
+    /// Render the image stretched over `effective_width` x `effective_height` cells
+    fn render_cells(&self, img: &image::DynamicImage, effective_width: u32, effective_height: u32) -> Vec<String> {
         let block_config = &self.config.display.block_rendering;
-        let mut output_lines = Vec::new();
         
-        // Try to load and process the image
-        match image::open(image_path) {
-            Ok(img) => {
-                // Resize the image to fit our dimensions
-                let resized = img.resize_exact(
-                    effective_width * 2, // Each character represents 2 pixels horizontally
-                    effective_height,     // Each character represents 1 pixel vertically
-                    image::imageops::FilterType::Lanczos3
+        let mut output_lines = match block_config.block_style.as_str() {
+            "halfblock" => self.render_halfblock_lines(img, effective_width, effective_height, block_config),
+            "quadrant" => self.render_subcell_lines(img, effective_width, effective_height, (2, 2), quadrant_glyph, block_config),
+            "sextant" => self.render_subcell_lines(img, effective_width, effective_height, (2, 3), sextant_glyph, block_config),
+            "braille" => self.render_subcell_lines(img, effective_width, effective_height, (2, 4), braille_glyph, block_config),
+            _ => self.render_glyph_lines(img, effective_width, effective_height, block_config),
+        };
+        
+        // Ensure we have the right number of lines
+        while output_lines.len() < effective_height as usize {
+            output_lines.push(" ".repeat(effective_width as usize));
+        }
+        output_lines.truncate(effective_height as usize);
+        
+        output_lines
+    }
+    
+    /// Frames of an animated logo, if animations are enabled and the image has more than one frame
+    fn animation_frames(&self, image_path: &std::path::Path) -> Option<Vec<AnimationFrame>> {
+        if !self.config.display.animation.enabled {
+            return None;
+        }
+        crate::animation::load_frames(image_path)
+    }
+    
+    /// Play an animated logo in place of the block-rendered first frame, which is `lines_below_top`
+    /// lines above the cursor. Stops on the last frame after the configured loops, or earlier
+    /// if the configured duration would be exceeded, so the prompt is never held up longer.
+    fn play_block_animation(&self, frames: &[AnimationFrame], lines_below_top: usize) {
+        use std::io::{IsTerminal, Write};
+        use std::time::{Duration, Instant};
+        
+        // Moving the cursor up only works while the image is still on screen
+        if !std::io::stdout().is_terminal()
+            || crate::terminal::rows().is_none_or(|rows| lines_below_top >= rows as usize)
+        {
+            return;
+        }
+        
+        let animation = &self.config.display.animation;
+        let deadline = Instant::now() + Duration::from_millis(animation.max_duration_ms);
+        let image_width = self.config.display.image_size.width as usize;
+        
+        let draw = |frame: &AnimationFrame| {
+            let lines = self.render_frame_as_text_blocks(&image::DynamicImage::ImageRgba8(frame.image.clone()));
+            let mut output = String::new();
+            output.push_str("\x1b7"); // Save cursor position
+            output.push_str(&format!("\x1b[{}A", lines_below_top));
+            for line in lines {
+                let visible_width = self.get_visible_width(&line);
+                output.push_str(&format!("\x1b[1G{}{}\x1b[1B", line, " ".repeat(image_width.saturating_sub(visible_width))));
+            }
+            output.push_str("\x1b8"); // Restore cursor position
+            print!("{}", output);
+            let _ = std::io::stdout().flush();
+        };
+        
+        // 0 loops means "as long as the duration allows" in block mode
+        let loops = if animation.loops == 0 { usize::MAX } else { animation.loops as usize };
+        let mut shown = 0; // The first frame is already on screen
+        let mut delay = frames[0].delay_ms;
+        let sequence = (0..frames.len()).cycle().skip(1).take((frames.len() * loops).saturating_sub(1));
+        for index in sequence {
+            if Instant::now() + Duration::from_millis(delay as u64) > deadline {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(delay as u64));
+            draw(&frames[index]);
+            shown = index;
+            delay = frames[index].delay_ms;
+        }
+        
+        // Always end on the last frame, even when the time ran out mid-animation
+        if shown != frames.len() - 1 {
+            draw(&frames[frames.len() - 1]);
+        }
+    }
+    
+    /// One brightness-selected glyph per cell, colored with the average of two horizontal pixels
+    fn render_glyph_lines(
+        &self,
+        img: &image::DynamicImage,
+        effective_width: u32,
+        effective_height: u32,
+        block_config: &crate::config::BlockRenderingConfig,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+        
+        // Resize the image to fit our dimensions
+        let resized = img.resize_exact(
+            effective_width * 2, // Each character represents 2 pixels horizontally
+            effective_height,     // Each character represents 1 pixel vertically
+            image::imageops::FilterType::Lanczos3
+        );
+        
+        // Blend onto the terminal background for easier processing, but keep the alpha
+        // channel to leave transparent cells empty
+        let rgba_img = resized.to_rgba8();
+        let (img_width, img_height) = rgba_img.dimensions();
+        let rgb_img = image::RgbImage::from_fn(img_width, img_height, |x, y| {
+            let (r, g, b) = self.blend_with_background(rgba_img.get_pixel(x, y));
+            image::Rgb([r, g, b])
+        });
+        
+        // Glyph selection and palette reduction are dithered independently
+        let dither_method = DitherMethod::from_config(block_config.dithering_method());
+        let mut glyph_dither = Ditherer::new(dither_method, effective_width, effective_height);
+        let mut color_dither = Ditherer::new(dither_method, effective_width, effective_height);
+        let level_spacing = 1.0 / (block_config.brightness_thresholds.len() + 1) as f32;
+        
+        // Convert image to colored block characters
+        for y in 0..effective_height {
+            let mut line = String::new();
+            
+            for x in 0..effective_width {
+                // Fully transparent cells stay plain spaces
+                let transparent = [x * 2, x * 2 + 1].iter().all(|&px| {
+                    rgba_img.get_pixel(px.min(img_width - 1), y.min(img_height - 1))[3] < TRANSPARENT_ALPHA
+                });
+                if transparent {
+                    glyph_dither.skip();
+                    color_dither.skip();
+                    line.push(' ');
+                    continue;
+                }
+                
+                // Sample pixels based on sampling method
+                let (avg_r, avg_g, avg_b, brightness) = self.sample_pixels(
+                    &rgb_img, x, y, img_width, img_height, block_config
                 );
                 
-                // Convert to RGB for easier processing
-                let rgb_img = resized.to_rgb8();
-                let (img_width, img_height) = rgb_img.dimensions();
+                // Apply brightness boost and contrast
+                let adjusted_brightness = self.adjust_brightness_contrast(
+                    brightness, block_config.brightness_boost, block_config.contrast
+                );
                 
-                // Convert image to colored block characters
-                for y in 0..effective_height as u32 {
-                    let mut line = String::new();
-                    
-                    for x in 0..effective_width as u32 {
-                        // Sample pixels based on sampling method
-                        let (avg_r, avg_g, avg_b, brightness) = self.sample_pixels(
-                            &rgb_img, x, y, img_width, img_height, block_config
-                        );
-                        
-                        // Apply brightness boost and contrast
-                        let adjusted_brightness = self.adjust_brightness_contrast(
-                            brightness, block_config.brightness_boost, block_config.contrast
-                        );
-                        
-                        // Choose appropriate block character based on brightness and style
-                        let block_char = self.select_block_character(adjusted_brightness, block_config);
-                        
-                        // Apply color based on color mode
-                        let colored_char = self.apply_color_mode(
-                            &block_char, avg_r, avg_g, avg_b, block_config
-                        );
-                        
-                        line.push_str(&colored_char);
+                // Choose appropriate block character based on brightness and style
+                let block_char = glyph_dither.apply([adjusted_brightness, 0.0, 0.0], level_spacing, |value| {
+                    let brightness = value[0].clamp(0.0, 1.0);
+                    let level = Self::brightness_level(brightness, &block_config.brightness_thresholds);
+                    (self.select_block_character(brightness, block_config), [level, 0.0, 0.0])
+                });
+                
+                // Apply color based on color mode
+                let colored_char = self.apply_color_mode(
+                    &block_char, avg_r, avg_g, avg_b, block_config, &mut color_dither
+                );
+                
+                line.push_str(&colored_char);
+            }
+            
+            // Reset color at end of line if needed
+            if block_config.color_mode != "monochrome" && !line.is_empty() {
+                line.push_str("\x1b[0m");
+            }
+            lines.push(line);
+        }
+        
+        lines
+    }
+    
+    /// Two vertically stacked pixels per cell: "▀" with the top pixel as foreground and the
+    /// bottom pixel as background. Transparent pixels keep the terminal's default background.
+    fn render_halfblock_lines(
+        &self,
+        img: &image::DynamicImage,
+        effective_width: u32,
+        effective_height: u32,
+        block_config: &crate::config::BlockRenderingConfig,
+    ) -> Vec<String> {
+        let resized = img
+            .resize_exact(effective_width, effective_height * 2, image::imageops::FilterType::Lanczos3)
+            .to_rgba8();
+        
+        // Monochrome pixels darker than the lowest brightness threshold stay blank like in the other styles
+        let cutoff = block_config.brightness_thresholds.iter().copied().fold(1.0, f32::min);
+        
+        // Foreground and background colors are dithered independently
+        let dither_method = DitherMethod::from_config(block_config.dithering_method());
+        let mut foreground_dither = Ditherer::new(dither_method, effective_width, effective_height);
+        let mut background_dither = Ditherer::new(dither_method, effective_width, effective_height);
+        
+        let mut lines = Vec::new();
+        for y in 0..effective_height {
+            let mut line = String::new();
+            let mut background_set = false;
+            
+            for x in 0..effective_width {
+                let top = self.adjusted_pixel(resized.get_pixel(x, y * 2), block_config);
+                let bottom = self.adjusted_pixel(resized.get_pixel(x, y * 2 + 1), block_config);
+                
+                if block_config.color_mode == "monochrome" {
+                    // Without colors the glyph itself has to show which halves are lit
+                    let lit = |pixel: Option<Rgb>| {
+                        pixel.is_some_and(|(r, g, b)| {
+                            (r as f32 * 0.299 + g as f32 * 0.587 + b as f32 * 0.114) / 255.0 > cutoff
+                        })
+                    };
+                    line.push(match (lit(top), lit(bottom)) {
+                        (true, true) => '█',
+                        (true, false) => '▀',
+                        (false, true) => '▄',
+                        (false, false) => ' ',
+                    });
+                    continue;
+                }
+                
+                match (top, bottom) {
+                    (Some(top), Some(bottom)) => {
+                        line.push_str(&self.color_escape(top, false, block_config, &mut foreground_dither));
+                        line.push_str(&self.color_escape(bottom, true, block_config, &mut background_dither));
+                        background_set = true;
+                        line.push('▀');
                     }
-                    
-                    // Reset color at end of line if needed
-                    if block_config.color_mode != "monochrome" && !line.is_empty() {
-                        line.push_str("\x1b[0m");
+                    (Some(top), None) => {
+                        line.push_str(&self.color_escape(top, false, block_config, &mut foreground_dither));
+                        background_dither.skip();
+                        Self::reset_background(&mut line, &mut background_set);
+                        line.push('▀');
+                    }
+                    (None, Some(bottom)) => {
+                        line.push_str(&self.color_escape(bottom, false, block_config, &mut foreground_dither));
+                        background_dither.skip();
+                        Self::reset_background(&mut line, &mut background_set);
+                        line.push('▄');
+                    }
+                    (None, None) => {
+                        foreground_dither.skip();
+                        background_dither.skip();
+                        Self::reset_background(&mut line, &mut background_set);
+                        line.push(' ');
                     }
-                    output_lines.push(line);
                 }
-            },
-            Err(_) => {
-                // If image loading fails, create placeholder lines
-                for _ in 0..effective_height as usize {
-                    output_lines.push(" ".repeat(effective_width as usize));
+            }
+            
+            if block_config.color_mode != "monochrome" {
+                line.push_str("\x1b[0m");
+            }
+            lines.push(line);
+        }
+        
+        lines
+    }
+    
+    /// A grid of `cell_pixels` (columns x rows) sub-pixels per cell, drawn with a glyph that
+    /// lights the matching sub-cells. Each cell's pixels are split into a foreground and a
+    /// background group, and both are drawn in the average color of their group.
+    fn render_subcell_lines(
+        &self,
+        img: &image::DynamicImage,
+        effective_width: u32,
+        effective_height: u32,
+        cell_pixels: (u32, u32),
+        glyph: fn(u8) -> char,
+        block_config: &crate::config::BlockRenderingConfig,
+    ) -> Vec<String> {
+        let (columns, rows) = cell_pixels;
+        let resized = img
+            .resize_exact(effective_width * columns, effective_height * rows, image::imageops::FilterType::Lanczos3)
+            .to_rgba8();
+        
+        // Monochrome pixels darker than the lowest brightness threshold stay blank like in the other styles
+        let cutoff = block_config.brightness_thresholds.iter().copied().fold(1.0, f32::min);
+        
+        // Foreground and background colors are dithered independently
+        let dither_method = DitherMethod::from_config(block_config.dithering_method());
+        let mut foreground_dither = Ditherer::new(dither_method, effective_width, effective_height);
+        let mut background_dither = Ditherer::new(dither_method, effective_width, effective_height);
+        
+        let mut lines = Vec::new();
+        for y in 0..effective_height {
+            let mut line = String::new();
+            let mut background_set = false;
+            
+            for x in 0..effective_width {
+                // Sub-pixels in row-major order; bit i of a mask stands for pixels[i]
+                let mut pixels = Vec::with_capacity((columns * rows) as usize);
+                for row in 0..rows {
+                    for column in 0..columns {
+                        let pixel = resized.get_pixel(x * columns + column, y * rows + row);
+                        pixels.push(self.adjusted_pixel(pixel, block_config));
+                    }
+                }
+                
+                if block_config.color_mode == "monochrome" {
+                    let mask = pixels.iter().enumerate().fold(0u8, |mask, (i, pixel)| {
+                        let lit = pixel.is_some_and(|(r, g, b)| {
+                            (r as f32 * 0.299 + g as f32 * 0.587 + b as f32 * 0.114) / 255.0 > cutoff
+                        });
+                        if lit { mask | 1 << i } else { mask }
+                    });
+                    line.push(glyph(mask));
+                    continue;
+                }
+                
+                let (mask, foreground, background) = Self::split_cell(&pixels);
+                if mask == 0 {
+                    foreground_dither.skip();
+                    background_dither.skip();
+                    Self::reset_background(&mut line, &mut background_set);
+                    line.push(' ');
+                    continue;
+                }
+                match foreground {
+                    Some(color) => line.push_str(&self.color_escape(color, false, block_config, &mut foreground_dither)),
+                    None => foreground_dither.skip(),
+                }
+                match background {
+                    Some(color) => {
+                        line.push_str(&self.color_escape(color, true, block_config, &mut background_dither));
+                        background_set = true;
+                    }
+                    None => {
+                        background_dither.skip();
+                        Self::reset_background(&mut line, &mut background_set);
+                    }
                 }
+                line.push(glyph(mask));
             }
+            
+            if block_config.color_mode != "monochrome" {
+                line.push_str("\x1b[0m");
+            }
+            lines.push(line);
         }
         
-        // Ensure we have the right number of lines
-        while output_lines.len() < effective_height as usize {
-            output_lines.push(" ".repeat(effective_width as usize));
+        lines
+    }
+    
+    /// Split a cell's pixels into foreground (mask bits set) and background, returning the mask and
+    /// the average color of each group. Transparent pixels are background without a color, so the
+    /// shape of transparent logos comes from their alpha channel. Opaque cells are split at the middle
+    /// of the color channel with the widest range.
+    fn split_cell(pixels: &[Option<Rgb>]) -> (u8, Option<Rgb>, Option<Rgb>) {
+        let average = |selected: &mut dyn Iterator<Item = Rgb>| {
+            let (mut r, mut g, mut b, mut count) = (0u32, 0u32, 0u32, 0u32);
+            for (pr, pg, pb) in selected {
+                r += pr as u32;
+                g += pg as u32;
+                b += pb as u32;
+                count += 1;
+            }
+            (count > 0).then(|| ((r / count) as u8, (g / count) as u8, (b / count) as u8))
+        };
+        
+        let opaque_mask = pixels.iter().enumerate().fold(0u8, |mask, (i, pixel)| {
+            if pixel.is_some() { mask | 1 << i } else { mask }
+        });
+        if pixels.iter().any(Option::is_none) {
+            return (opaque_mask, average(&mut pixels.iter().flatten().copied()), None);
         }
-        output_lines.truncate(effective_height as usize);
         
-        Ok(output_lines)
+        let channel = |pixel: Rgb, index: usize| [pixel.0, pixel.1, pixel.2][index];
+        let colors: Vec<Rgb> = pixels.iter().flatten().copied().collect();
+        let (split_channel, low, high) = (0..3)
+            .map(|index| {
+                let low = colors.iter().map(|&color| channel(color, index)).min().unwrap_or(0);
+                let high = colors.iter().map(|&color| channel(color, index)).max().unwrap_or(0);
+                (index, low, high)
+            })
+            .max_by_key(|&(_, low, high)| high - low)
+            .unwrap_or((0, 0, 0));
+        
+        // Nearly uniform cells are drawn as one solid color
+        if high - low < 16 {
+            return (opaque_mask, average(&mut colors.iter().copied()), None);
+        }
+        
+        let middle = ((low as u16 + high as u16) / 2) as u8;
+        let mask = colors.iter().enumerate().fold(0u8, |mask, (i, &color)| {
+            if channel(color, split_channel) > middle { mask | 1 << i } else { mask }
+        });
+        let foreground = average(&mut colors.iter().copied().filter(|&color| channel(color, split_channel) > middle));
+        let background = average(&mut colors.iter().copied().filter(|&color| channel(color, split_channel) <= middle));
+        (mask, foreground, background)
+    }
+    
+    /// Switch back to the terminal's default background, only emitting a code if a color was set
+    fn reset_background(line: &mut String, background_set: &mut bool) {
+        if *background_set {
+            line.push_str("\x1b[49m");
+            *background_set = false;
+        }
+    }
+    
+    /// Color of one pixel after blending and brightness/contrast adjustment, None if transparent
+    fn adjusted_pixel(&self, pixel: &image::Rgba<u8>, block_config: &crate::config::BlockRenderingConfig) -> Option<Rgb> {
+        if pixel[3] < TRANSPARENT_ALPHA {
+            return None;
+        }
+        let pixel = self.blend_with_background(pixel);
+        
+        let adjust = |channel: u8| {
+            let value = self.adjust_brightness_contrast(
+                channel as f32 / 255.0, block_config.brightness_boost, block_config.contrast
+            );
+            (value * 255.0).round() as u8
+        };
+        Some((adjust(pixel.0), adjust(pixel.1), adjust(pixel.2)))
+    }
+    
+    /// Composite a pixel onto the terminal background according to its alpha
+    fn blend_with_background(&self, pixel: &image::Rgba<u8>) -> Rgb {
+        let (br, bg, bb) = self.background_color();
+        let alpha = pixel[3] as u16;
+        let blend = |channel: u8, background: u8| {
+            ((channel as u16 * alpha + background as u16 * (255 - alpha) + 127) / 255) as u8
+        };
+        (blend(pixel[0], br), blend(pixel[1], bg), blend(pixel[2], bb))
+    }
+    
+    /// Configured background color, or the one the terminal reports for "auto" (black if it doesn't)
+    fn background_color(&self) -> Rgb {
+        *self.background.get_or_init(|| {
+            match self.config.display.block_rendering.background_color.as_str() {
+                "auto" => crate::terminal::background_color(),
+                color => self.parse_hex_color(color),
+            }
+            .unwrap_or((0, 0, 0))
+        })
     }
     
     fn get_visible_width(&self, text: &str) -> usize {
@@ -220,6 +686,10 @@ impl Display {
             config: Config::default(),
             show_images: false,
             kitty_graphics: KittyGraphics::new(),
+            sixel_graphics: SixelGraphics::new(),
+            iterm2_graphics: Iterm2Graphics::new(),
+            cell_size: (0, 0),
+            background: std::cell::OnceCell::new(),
         };
         
         println!("{}", temp_display.apply_color(message, &motd_config.color));
@@ -227,20 +697,39 @@ impl Display {
 
     
     fn render_image_to_terminal(&self, image_path: &std::path::Path) -> Result<()> {
-        // Try Kitty Graphics Protocol if preferred and supported
-        if self.config.display.prefer_kitty_graphics && self.kitty_graphics.supports_kitty {
-            let width = self.config.display.image_size.width;
-            let height = self.config.display.image_size.height;
-            let cell_width = self.config.display.image_size.cell_width;
-            let cell_height = self.config.display.image_size.cell_height;
-            match self.kitty_graphics.render_image(image_path, width, height, cell_width, cell_height) {
-                Ok(_) => {
-                    return Ok(());
+        let width = self.config.display.image_size.width;
+        let height = self.config.display.image_size.height;
+        let (cell_width, cell_height) = self.cell_size;
+
+        // Try the selected graphics protocol first
+        match self.graphics_backend() {
+            Some(GraphicsBackend::Kitty) => {
+                // Animations are played by the terminal itself
+                let result = match self.animation_frames(image_path) {
+                    Some(frames) => {
+                        let loops = self.config.display.animation.loops;
+                        self.kitty_graphics.render_animation(&frames, width, height, cell_width, cell_height, loops)
+                    }
+                    None => self.kitty_graphics.render_image(image_path, width, height, cell_width, cell_height),
+                };
+                match result {
+                    Ok(_) => return Ok(()),
+                    Err(e) => eprintln!("[Warning] Kitty Graphics failed: {}, falling back to block rendering", e),
+                }
+            }
+            Some(GraphicsBackend::Sixel) => {
+                match self.sixel_graphics.render_image(image_path, width, height, cell_width, cell_height) {
+                    Ok(_) => return Ok(()),
+                    Err(e) => eprintln!("[Warning] Sixel failed: {}, falling back to block rendering", e),
                 }
-                Err(e) => {
-                    eprintln!("[Warning] Kitty Graphics failed: {}, falling back to block rendering", e);
+            }
+            Some(GraphicsBackend::Iterm2) => {
+                match self.iterm2_graphics.render_image(image_path, width, height, cell_width, cell_height) {
+                    Ok(_) => return Ok(()),
+                    Err(e) => eprintln!("[Warning] iTerm2 inline image failed: {}, falling back to block rendering", e),
                 }
             }
+            None => {}
         }
         
         // Fallback to our custom block rendering for all other terminals
@@ -262,8 +751,8 @@ impl Display {
         let padding = self.config.display.padding as usize;
         let border_color = &self.config.display.border_color;
         
-        // For Kitty Graphics Protocol, we need to implement true side-by-side layout
-        if self.config.display.prefer_kitty_graphics && self.is_kitty_terminal() {
+        // For pixel graphics protocols, we need to implement true side-by-side layout
+        if self.graphics_backend().is_some() {
             // Calculate dimensions
             let image_height = self.config.display.image_size.height as usize;
             let info_start_col = image_width + padding;
@@ -281,11 +770,13 @@ impl Display {
             // Move cursor back to the beginning of our reserved space
             print!("\x1b[{}A", total_space_needed);
             
-            // Render the image with Kitty Graphics Protocol at current position
+            // Render the image at current position. The protocols leave the cursor at different
+            // rows below the image, so save and restore it to get back to the top of the image.
+            print!("\x1b7");
             self.render_image_to_terminal(image_path)?;
+            print!("\x1b8");
             
-            // Move cursor back up to align with top of image for module output
-            print!("\x1b[{}A", image_height);
+            // Move cursor right to align with the image for module output
             print!("\x1b[{}C", info_start_col); // Move cursor right to info column
             
             // Render top border for module area if enabled
@@ -313,11 +804,8 @@ impl Display {
             }
             
             // Move cursor to the end of our reserved space
-            let remaining_lines = if total_space_needed > (image_height + info_lines.len() + border_height) {
-                total_space_needed - (image_height + info_lines.len() + border_height)
-            } else {
-                0
-            };
+            let printed_lines = info_lines.len() + if self.config.display.show_border { 2 } else { 0 };
+            let remaining_lines = total_space_needed.saturating_sub(printed_lines);
             
             if remaining_lines > 0 {
                 print!("\x1b[{}B", remaining_lines);
@@ -334,14 +822,77 @@ impl Display {
     }
     
     fn show_horizontal_layout_with_blocks(&self, image_path: &std::path::Path, info_lines: &[String]) -> Result<()> {
-        let border_color = &self.config.display.border_color;
-        
         // Use block-based rendering for true side-by-side layout
         // This works in all terminals, including Kitty/Ghostty
-        let image_lines = self.render_image_as_text_blocks(&image_path.to_path_buf())?;
+        let frames = self.animation_frames(image_path);
+        let image_lines = match &frames {
+            Some(frames) => self.render_frame_as_text_blocks(&image::DynamicImage::ImageRgba8(frames[0].image.clone())),
+            None => self.render_image_as_text_blocks(&image_path.to_path_buf())?,
+        };
         
-        // Calculate layout dimensions
         let image_width = self.config.display.image_size.width as usize;
+        let lines_printed = self.print_side_by_side(&image_lines, image_width, info_lines);
+        
+        // Animate the logo in place now that the info is already visible
+        if let Some(frames) = frames {
+            self.play_block_animation(&frames, lines_printed);
+        }
+        
+        Ok(())
+    }
+    
+    /// The text logo to show when there is no image, None if text logos are disabled
+    fn ascii_logo(&self) -> Option<AsciiLogo> {
+        let logo_config = &self.config.display.ascii_logo;
+        if !logo_config.enabled {
+            return None;
+        }
+        
+        if logo_config.logo != "auto" {
+            if let Some(logo) = AsciiLogo::builtin(&logo_config.logo) {
+                return Some(logo);
+            }
+            match AsciiLogo::from_file(&expand_tilde(std::path::Path::new(&logo_config.logo))) {
+                Ok(logo) => return Some(logo),
+                Err(e) => eprintln!("[Warning] {}, using the built-in logo", e),
+            }
+        }
+        
+        Some(AsciiLogo::for_os(&SystemInfo::os_release_ids()))
+    }
+    
+    fn show_horizontal_layout_with_text_logo(&self, logo: &AsciiLogo, info_lines: &[String]) {
+        let colors = &self.config.display.ascii_logo.colors;
+        let logo_lines = logo.render(|index| {
+            let color = colors
+                .get(index - 1)
+                .map(String::as_str)
+                .or_else(|| logo.default_colors.get(index - 1).copied())
+                .unwrap_or("");
+            self.text_logo_color(color)
+        });
+        
+        self.print_side_by_side(&logo_lines, logo.width(), info_lines);
+    }
+    
+    /// Escape sequence for a text logo color: "#RRGGBB", or an ANSI color number which
+    /// follows the terminal's palette and also works on the Linux console
+    fn text_logo_color(&self, color: &str) -> String {
+        if let Some((r, g, b)) = self.parse_hex_color(color) {
+            return format!("\x1b[38;2;{};{};{}m", r, g, b);
+        }
+        match color.parse::<u8>() {
+            Ok(index @ 0..=7) => format!("\x1b[{}m", 30 + index),
+            Ok(index @ 8..=15) => format!("\x1b[{}m", 90 + index - 8),
+            Ok(index) => format!("\x1b[38;5;{}m", index),
+            Err(_) => "\x1b[39m".to_string(),
+        }
+    }
+    
+    /// Print the logo lines next to the info lines, the logo column padded to `image_width`.
+    /// Returns how many lines were printed below the first logo line.
+    fn print_side_by_side(&self, image_lines: &[String], image_width: usize, info_lines: &[String]) -> usize {
+        let border_color = &self.config.display.border_color;
         let padding = self.config.display.padding;
         
         // Always show ALL info lines, even if they exceed image height
@@ -402,9 +953,10 @@ impl Display {
         // Print bottom border for module area if enabled
         if self.config.display.show_border {
             println!("{}", border_bottom_line);
+            return max_lines + 1;
         }
         
-        Ok(())
+        max_lines
     }
 
     fn show_info_only(&self, info_lines: &[String]) {
@@ -434,49 +986,29 @@ impl Display {
     }
     
     fn get_module_display_name(&self, module_key: &str, default_name: &str) -> String {
-        let display_names = &self.config.modules.display_names;
-        let custom_name = match module_key {
-            "user_at_host" => &display_names.user_at_host,
-            "os" => &display_names.os,
-            "kernel" => &display_names.kernel,
-            "linux" => &display_names.linux,
-            "uptime" => &display_names.uptime,
-            "os_age" => &display_names.os_age,
-            "packages" | "packages_combined" => &display_names.packages,
-            "shell" => &display_names.shell,
-            "resolution" => &display_names.resolution,
-            "network" => &display_names.network,
-            "public_ip" => &display_names.public_ip,
-            "de" => &display_names.de,
-            "wm" => &display_names.wm,
-            "theme" => &display_names.theme,
-            "icons" => &display_names.icons,
-            "terminal" => &display_names.terminal,
-            "terminal_shell_combined" => &display_names.terminal_shell_combined,
-            "font" => &display_names.font,
-            "user" => &display_names.user,
-            "hostname" => &display_names.hostname,
-            "cpu" => &display_names.cpu,
-            "cpu_temp" => &display_names.cpu_temp,
-            "gpu" => &display_names.gpu,
-            "gpu_temp" => &display_names.gpu_temp,
-            "temp_combined" => &display_names.temp_combined,
-            "gpu_driver" => &display_names.gpu_driver,
-            "memory" => &display_names.memory,
-            "disk" => &display_names.disk,
-            "dysk" => &display_names.dysk,
-            "battery" => &display_names.battery,
-            "locale" => &display_names.locale,
-            _ => &None,
-        };
-        
-        custom_name.as_ref().unwrap_or(&default_name.to_string()).clone()
+        self.config.modules.display_names.get(module_key)
+            .cloned()
+            .unwrap_or_else(|| default_name.to_string())
     }
 
 
 
-    fn prepare_system_info_lines(&self, system_info: &SystemInfo) -> Vec<String> {
+    /// Display name for an enabled module (built-in or custom), or None if it is disabled
+    fn enabled_module_name(&self, module_key: &str) -> Option<String> {
         let modules = &self.config.modules;
+        if let Some(custom) = modules.custom.iter().find(|custom| custom.key() == module_key) {
+            return Some(custom.name.clone());
+        }
+        
+        let (_, default_name) = MODULES.iter().find(|(key, _)| *key == module_key)?;
+        if modules.is_enabled(module_key) {
+            Some(self.get_module_display_name(module_key, default_name))
+        } else {
+            None
+        }
+    }
+
+    fn prepare_system_info_lines(&self, system_info: &SystemInfo) -> Vec<String> {
         let colors = &self.config.general.colors;
         let separator_config = &self.config.general.separator;
         let mut lines = Vec::new();
@@ -489,120 +1021,103 @@ impl Display {
             " ".repeat(separator_config.space_after as usize)
         );
 
-        // Define the order and mapping of modules
-        let module_order = [
-            ("user_at_host", "Login", modules.user_at_host),
-            ("user", "User", modules.user),
-            ("hostname", "Hostname", modules.hostname),            
-            ("os", "OS", modules.os),
-            ("kernel", "Kernel", modules.kernel),
-            ("linux", "Linux", modules.linux),
-            ("uptime", "Uptime", modules.uptime),
-            ("os_age", "OS Age", modules.os_age),
-            ("packages", "Packages", modules.packages),
-            ("flatpak_packages", "Flatpak", modules.flatpak_packages),
-            ("packages_combined", "Packages", modules.packages_combined),
-            ("shell", "Shell", modules.shell),
-            ("terminal", "Terminal", modules.terminal),
-            ("terminal_shell_combined", "Terminal & Shell", modules.terminal_shell_combined),            
-            ("resolution", "Resolution", modules.resolution),
-            ("de", "DE", modules.de),
-            ("wm", "WM", modules.wm),
-            ("theme", "Theme", modules.theme),
-            ("icons", "Icons", modules.icons),
-            ("font", "Font", modules.font),
-            ("locale", "Locale", modules.locale),            
-            ("cpu", "CPU", modules.cpu),
-            ("cpu_temp", "CPU Temp", modules.cpu_temp),
-            ("gpu", "GPU", modules.gpu),
-            ("gpu_temp", "GPU Temp", modules.gpu_temp),
-            ("gpu_driver", "GPU Driver", modules.gpu_driver),
-            ("temp_combined", "Temperatures", modules.temp_combined),            
-            ("memory", "Memory", modules.memory),
-            ("battery", "Battery", modules.battery),
-            ("network", "Network", modules.network),
-            ("public_ip", "Public IP", modules.public_ip),
-            ("disk", "Disk", modules.disk),            
-            ("dysk", "Drives", modules.dysk),
-        ];
+        // Module order from config (or the default order), with separators, blank lines and headers
+        let layout = self.config.modules.layout();
         
         // Calculate maximum module name width for alignment if enabled
         let max_name_width = if separator_config.align_separator {
-            module_order
+            layout
                 .iter()
-                .filter(|(_, _, enabled)| *enabled)
-                .map(|(key, default_name, _)| {
-                    let display_name = self.get_module_display_name(key, default_name);
-                    // Use standard unicode width for Nerd Font icons (consistent width)
-                    display_name.width()
+                .filter_map(|entry| match entry {
+                    OrderEntry::Module(key) => self.enabled_module_name(key),
+                    _ => None,
                 })
+                // Use standard unicode width for Nerd Font icons (consistent width)
+                .map(|display_name| display_name.width())
                 .max()
                 .unwrap_or(0)
         } else {
             0
         };
+        
+        // Separator rules span the widest module line, so they are filled in at the end
+        let mut rule_indices = Vec::new();
+        let mut max_line_width = 0;
 
-        for (key, default_name, enabled) in module_order {
-            if enabled {
-                let lookup_key = key.to_uppercase();
-                if let Some(value) = system_info.data.get(&lookup_key) {
-                    let trimmed_value = value.trim();
-                    // Only add non-empty, non-Unknown values
-                    if !trimmed_value.is_empty() && trimmed_value != "Unknown" {
-                        // Get custom display name (with potential icon)
-                        let display_name = self.get_module_display_name(key, default_name);
-                        
-                        // Pad module name for alignment if enabled
-                        let padded_name = if separator_config.align_separator {
-                            // Calculate visual width and pad accordingly
-                            let visual_width = display_name.width();
-                            let padding_needed = max_name_width.saturating_sub(visual_width);
-                            format!("{}{}", display_name, " ".repeat(padding_needed))
-                        } else {
-                            display_name
-                        };
-                        
-                        // Handle multi-line modules (like dysk)
-                        let value_lines: Vec<&str> = trimmed_value.lines().collect();
+        for entry in layout {
+            let key = match entry {
+                OrderEntry::Module(key) => key,
+                OrderEntry::Separator => {
+                    rule_indices.push(lines.len());
+                    lines.push(String::new());
+                    continue;
+                }
+                OrderEntry::Blank => {
+                    lines.push(String::new());
+                    continue;
+                }
+                OrderEntry::Header(title) => {
+                    max_line_width = max_line_width.max(title.width());
+                    lines.push(self.apply_color(&title, &colors.title).to_string());
+                    continue;
+                }
+            };
+            
+            let Some(display_name) = self.enabled_module_name(&key) else {
+                continue;
+            };
+            
+            let lookup_key = key.to_uppercase();
+            if let Some(value) = system_info.data.get(&lookup_key) {
+                let formatted_value = self.format_module_value(&key, value);
+                let trimmed_value = formatted_value.trim();
+                // Only add non-empty, non-Unknown values
+                if !trimmed_value.is_empty() && trimmed_value != "Unknown" {
+                    // Pad module name for alignment if enabled
+                    let padded_name = if separator_config.align_separator {
+                        // Calculate visual width and pad accordingly
+                        let visual_width = display_name.width();
+                        let padding_needed = max_name_width.saturating_sub(visual_width);
+                        format!("{}{}", display_name, " ".repeat(padding_needed))
+                    } else {
+                        display_name
+                    };
+                    
+                    // Handle multi-line modules (like dysk)
+                    let value_lines: Vec<&str> = trimmed_value.lines().collect();
+                    let indent_width = padded_name.width() + separator.width();
+                    
+                    for (i, value_line) in value_lines.iter().enumerate() {
+                        max_line_width = max_line_width.max(indent_width + value_line.width());
                         
-                        if value_lines.len() > 1 {
-                            // Multi-line module: first line with module name, subsequent lines indented
-                            for (i, value_line) in value_lines.iter().enumerate() {
-                                if i == 0 {
-                                    // First line with module name
-                                    let line = format!(
-                                        "{}{}{}",
-                                        self.apply_color(&padded_name, &colors.module),
-                                        self.apply_color(&separator, &colors.separator),
-                                        self.apply_color(value_line, &colors.info)
-                                    );
-                                    lines.push(line);
-                                } else {
-                                    // Subsequent lines: indent to align with the value column
-                                    let indent_width = padded_name.width() + separator.width();
-                                    let indented_line = format!(
-                                        "{}{}",
-                                        " ".repeat(indent_width),
-                                        self.apply_color(value_line, &colors.info)
-                                    );
-                                    lines.push(indented_line);
-                                }
-                            }
-                        } else {
-                            // Single-line module
+                        if i == 0 {
+                            // First line with module name
                             let line = format!(
                                 "{}{}{}",
                                 self.apply_color(&padded_name, &colors.module),
                                 self.apply_color(&separator, &colors.separator),
-                                self.apply_color(trimmed_value, &colors.info)
+                                self.apply_color(value_line, &colors.info)
                             );
                             lines.push(line);
+                        } else {
+                            // Subsequent lines: indent to align with the value column
+                            let indented_line = format!(
+                                "{}{}",
+                                " ".repeat(indent_width),
+                                self.apply_color(value_line, &colors.info)
+                            );
+                            lines.push(indented_line);
                         }
                     }
                 }
             }
         }
         
+        let rule = self.apply_color(&"─".repeat(max_line_width), &colors.separator).to_string();
+        for index in rule_indices {
+            lines[index] = rule.clone();
+        }
+        
         lines
     }
     
@@ -655,6 +1170,218 @@ impl Display {
         }
     }
     
+    // Helper methods for formatting gathered module data
+    
+    /// Format a module's gathered data as the text shown after the separator
+    fn format_module_value(&self, module_key: &str, data: &ModuleData) -> String {
+        match data {
+            ModuleData::Text(text) => text.clone(),
+            ModuleData::Memory(memory) => {
+                let percent = if memory.total_bytes > 0 {
+                    memory.used_bytes as f64 / memory.total_bytes as f64 * 100.0
+                } else {
+                    0.0
+                };
+                format!("{:.1}GB / {:.1}GB ({:.0}%)",
+                        Self::bytes_to_gib(memory.used_bytes), Self::bytes_to_gib(memory.total_bytes), percent)
+            },
+            // One line per GPU: name, VRAM, driver and temperature
+            ModuleData::Gpus(gpus) => gpus.iter()
+                .map(|gpu| {
+                    let mut line = match (gpu.vram_used_bytes, gpu.vram_total_bytes) {
+                        (Some(used), Some(total)) => format!("{} ({:.1}GB / {:.1}GB)",
+                                                             gpu.name, Self::bytes_to_gib(used), Self::bytes_to_gib(total)),
+                        (None, Some(total)) => format!("{} ({:.1}GB)", gpu.name, Self::bytes_to_gib(total)),
+                        _ => gpu.name.clone(),
+                    };
+                    if let Some(driver) = &gpu.driver {
+                        match &gpu.driver_version {
+                            Some(version) => line.push_str(&format!(" • {} {}", driver, version)),
+                            None => line.push_str(&format!(" • {}", driver)),
+                        }
+                    }
+                    if gpu.temperature_celsius.is_some() {
+                        line.push_str(&format!(" • {}", Self::format_temperature(gpu.temperature_celsius)));
+                    }
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ModuleData::Disks(disks) => {
+                if module_key == "disk" {
+                    // Compact single-line view joined with a bullet separator
+                    disks.iter()
+                        .map(|disk| format!("{} {} / {} ({}%) [{}]",
+                                            disk.mount_point,
+                                            Self::format_size(disk.used_bytes),
+                                            Self::format_size(disk.size_bytes),
+                                            disk.usage_percent(),
+                                            disk.filesystem))
+                        .collect::<Vec<_>>()
+                        .join(" • ")
+                } else {
+                    // dysk-style view with one line per drive
+                    disks.iter()
+                        .map(|disk| self.format_dysk_line(disk))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            },
+            ModuleData::Battery(battery) => format!("{}% ({})", battery.percent, battery.status),
+            ModuleData::Packages(packages) => packages.iter()
+                .map(|package| format!("{} ({})", package.count, package.manager))
+                .collect::<Vec<_>>()
+                .join(", "),
+            ModuleData::Temperature(celsius) => Self::format_temperature(*celsius),
+            ModuleData::Temperatures(summary) => match (summary.cpu_celsius, summary.gpu_celsius) {
+                (Some(cpu), Some(gpu)) => format!("CPU {} • GPU {}",
+                                                  Self::format_temperature(Some(cpu)), Self::format_temperature(Some(gpu))),
+                (Some(cpu), None) => format!("CPU {}", Self::format_temperature(Some(cpu))),
+                (None, Some(gpu)) => format!("GPU {}", Self::format_temperature(Some(gpu))),
+                (None, None) => "N/A".to_string(),
+            },
+            ModuleData::Sensors(sensors) if sensors.is_empty() => "N/A".to_string(),
+            ModuleData::Sensors(sensors) => Self::sensor_names(sensors).into_iter()
+                .zip(sensors)
+                .map(|(name, sensor)| format!("{} {}", name, Self::format_temperature(Some(sensor.celsius))))
+                .collect::<Vec<_>>()
+                .join(" • "),
+            ModuleData::Displays(outputs) => outputs.iter()
+                .map(|output| {
+                    let mut line = format!("{}x{}", output.width, output.height);
+                    if let Some(rate) = output.refresh_hz {
+                        line.push_str(&format!(" @ {:.0}Hz", rate));
+                    }
+                    if let Some(scale) = output.scale.filter(|scale| (scale - 1.0).abs() > f32::EPSILON) {
+                        let scale = format!("{:.2}", scale);
+                        line.push_str(&format!(" ({}x)", scale.trim_end_matches('0').trim_end_matches('.')));
+                    }
+                    // Only name outputs when there is more than one
+                    if outputs.len() > 1 {
+                        line = format!("{} {}", output.name, line);
+                    }
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join(" • "),
+        }
+    }
+    
+    /// Short names for temperature sensors: the role, plus the label when several sensors
+    /// share a role, plus a number when that still isn't unique (e.g. two NVMe drives)
+    fn sensor_names(sensors: &[TemperatureSensor]) -> Vec<String> {
+        let role_name = |sensor: &TemperatureSensor| match sensor.role.as_str() {
+            "cpu" => "CPU".to_string(),
+            "gpu" => "GPU".to_string(),
+            "nvme" => "NVMe".to_string(),
+            "disk" => "Disk".to_string(),
+            "chipset" => "Chipset".to_string(),
+            "board" => "Board".to_string(),
+            "ambient" => "Ambient".to_string(),
+            "wifi" => "WiFi".to_string(),
+            _ => sensor.chip.clone(),
+        };
+        
+        let names: Vec<String> = sensors.iter()
+            .map(|sensor| {
+                let shared_role = sensors.iter().filter(|other| other.role == sensor.role).count() > 1;
+                if shared_role && !sensor.label.is_empty() {
+                    format!("{} {}", role_name(sensor), sensor.label)
+                } else {
+                    role_name(sensor)
+                }
+            })
+            .collect();
+        
+        names.iter()
+            .enumerate()
+            .map(|(index, name)| {
+                if names.iter().filter(|other| *other == name).count() > 1 {
+                    let number = names[..=index].iter().filter(|other| *other == name).count();
+                    format!("{} {}", name, number)
+                } else {
+                    name.clone()
+                }
+            })
+            .collect()
+    }
+    
+    fn format_dysk_line(&self, disk: &DiskEntry) -> String {
+        let usage_percent = disk.usage_percent();
+        
+        // Clean device name (remove /dev/ prefix)
+        let clean_device = disk.device.strip_prefix("/dev/").unwrap_or(&disk.device);
+        
+        // For btrfs, show the mount point to indicate it's the consolidated view
+        let display_mount = if disk.filesystem != "btrfs" {
+            disk.mount_point.clone()
+        } else if disk.mount_point == "/" {
+            "/ (btrfs)".to_string()
+        } else {
+            format!("{} (btrfs main)", disk.mount_point)
+        };
+        
+        // Progress Bar, Percent (3 chars), Device Name, Usage (fixed width), Filesystem, Mount Point
+        format!(
+            "{} {:>3}% {} {:>4}/{:<4} [{}] {}",
+            Self::create_progress_bar(usage_percent),
+            usage_percent,
+            clean_device,
+            Self::format_size(disk.used_bytes),
+            Self::format_size(disk.size_bytes),
+            disk.filesystem,
+            display_mount
+        )
+    }
+    
+    fn create_progress_bar(usage_percent: u32) -> String {
+        let bar_length = 10;
+        let filled_length = (usage_percent * bar_length / 100).min(bar_length);
+        let empty_length = bar_length - filled_length;
+        
+        // Use different characters based on usage level
+        let (fill_char, empty_char) = if usage_percent >= 90 {
+            ("█", "░")  // Red zone - full blocks
+        } else if usage_percent >= 70 {
+            ("▓", "░")  // Yellow zone - medium blocks
+        } else {
+            ("▒", "░")  // Green zone - light blocks
+        };
+        
+        format!("[{}{}]", 
+                fill_char.repeat(filled_length as usize),
+                empty_char.repeat(empty_length as usize))
+    }
+    
+    fn format_temperature(celsius: Option<f32>) -> String {
+        match celsius {
+            Some(celsius) => format!("{:.1}°C", celsius),
+            None => "N/A".to_string(),
+        }
+    }
+    
+    fn bytes_to_gib(bytes: u64) -> f64 {
+        bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+    }
+    
+    /// Format a byte count like `df -h` does (e.g. "9.8G", "17G", "363M")
+    fn format_size(bytes: u64) -> String {
+        const UNITS: &[&str] = &["B", "K", "M", "G", "T", "P"];
+        let mut size = bytes as f64;
+        let mut unit_index = 0;
+        
+        while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit_index += 1;
+        }
+        
+        if unit_index == 0 || size >= 10.0 {
+            format!("{:.0}{}", size.ceil(), UNITS[unit_index])
+        } else {
+            format!("{:.1}{}", (size * 10.0).ceil() / 10.0, UNITS[unit_index])
+        }
+    }
+    
     // Helper methods for configurable block rendering
     
     fn sample_pixels(
@@ -722,10 +1449,21 @@ impl Display {
         contrasted
     }
     
+    /// Brightness a glyph stands for: the middle of the threshold interval it was selected from
+    fn brightness_level(brightness: f32, thresholds: &[f32]) -> f32 {
+        let mut upper = 1.0;
+        for &threshold in thresholds {
+            if brightness > threshold {
+                return (upper + threshold) / 2.0;
+            }
+            upper = threshold;
+        }
+        upper / 2.0
+    }
+    
     fn select_block_character(&self, brightness: f32, block_config: &crate::config::BlockRenderingConfig) -> String {
         let blocks = match block_config.block_style.as_str() {
             "ascii" => vec!["#", "*", ":", ".", " "],
-            "braille" => vec!["☣", "☢", "☖", "☄", " "],
             "custom" => {
                 if block_config.custom_blocks.is_empty() {
                     vec!["█", "▓", "▒", "░", " "]
@@ -756,30 +1494,101 @@ impl Display {
         g: u16,
         b: u16,
         block_config: &crate::config::BlockRenderingConfig,
+        dither: &mut Ditherer,
     ) -> String {
-        if block_char == " " {
-            return block_char.to_string();
-        }
+        let color = [r as f32, g as f32, b as f32];
+        let clamp = |value: [f32; 3]| value.map(|channel| channel.round().clamp(0.0, 255.0) as u8);
         
         match block_config.color_mode.as_str() {
             "monochrome" => block_char.to_string(),
             "16color" => {
                 // Convert to nearest 16-color ANSI code
-                let ansi_code = self.rgb_to_ansi16(r as u8, g as u8, b as u8);
+                let ansi_code = dither.apply(color, 128.0, |value| {
+                    let [r, g, b] = clamp(value);
+                    let ansi_code = self.rgb_to_ansi16(r, g, b);
+                    (ansi_code, Self::ansi16_to_rgb(ansi_code))
+                });
+                if block_char == " " {
+                    return block_char.to_string();
+                }
                 format!("\x1b[{}m{}", ansi_code, block_char)
             },
             "256color" => {
                 // Convert to 256-color ANSI code
-                let ansi_code = self.rgb_to_ansi256(r as u8, g as u8, b as u8);
+                let ansi_code = dither.apply(color, 51.0, |value| {
+                    let [r, g, b] = clamp(value);
+                    let ansi_code = self.rgb_to_ansi256(r, g, b);
+                    (ansi_code, Self::ansi256_to_rgb(ansi_code))
+                });
+                if block_char == " " {
+                    return block_char.to_string();
+                }
                 format!("\x1b[38;5;{}m{}", ansi_code, block_char)
             },
             _ => {
+                if block_char == " " {
+                    return block_char.to_string();
+                }
                 // Default: "truecolor"
                 format!("\x1b[38;2;{};{};{}m{}", r, g, b, block_char)
             }
         }
     }
     
+    /// Color an ANSI 16-color code stands for (VGA palette), the inverse of rgb_to_ansi16
+    fn ansi16_to_rgb(code: u8) -> [f32; 3] {
+        let bright = code >= 90;
+        let color = code - if bright { 90 } else { 30 };
+        let (on, off) = if bright { (255.0, 85.0) } else { (170.0, 0.0) };
+        [0, 1, 2].map(|bit| if color & (1 << bit) != 0 { on } else { off })
+    }
+    
+    /// Color of a 256-color code as produced by rgb_to_ansi256
+    fn ansi256_to_rgb(code: u8) -> [f32; 3] {
+        if code >= 232 {
+            let gray = 8.0 + (code - 232) as f32 * 10.0;
+            [gray; 3]
+        } else {
+            let index = code.saturating_sub(16);
+            [index / 36, (index / 6) % 6, index % 6].map(|level| level as f32 * 51.0)
+        }
+    }
+    
+    /// Escape sequence setting the foreground or background color in the configured color mode.
+    /// The palette reduction of the 16 and 256 color modes is dithered like in apply_color_mode.
+    fn color_escape(
+        &self,
+        (r, g, b): Rgb,
+        background: bool,
+        block_config: &crate::config::BlockRenderingConfig,
+        dither: &mut Ditherer,
+    ) -> String {
+        let color = [r as f32, g as f32, b as f32];
+        let clamp = |value: [f32; 3]| value.map(|channel| channel.round().clamp(0.0, 255.0) as u8);
+        
+        match block_config.color_mode.as_str() {
+            "monochrome" => String::new(),
+            "16color" => {
+                let ansi_code = dither.apply(color, 128.0, |value| {
+                    let [r, g, b] = clamp(value);
+                    let ansi_code = self.rgb_to_ansi16(r, g, b);
+                    (ansi_code, Self::ansi16_to_rgb(ansi_code))
+                });
+                // Background codes are the foreground codes + 10
+                format!("\x1b[{}m", ansi_code + if background { 10 } else { 0 })
+            },
+            "256color" => {
+                let ansi_code = dither.apply(color, 51.0, |value| {
+                    let [r, g, b] = clamp(value);
+                    let ansi_code = self.rgb_to_ansi256(r, g, b);
+                    (ansi_code, Self::ansi256_to_rgb(ansi_code))
+                });
+                format!("\x1b[{};5;{}m", if background { 48 } else { 38 }, ansi_code)
+            },
+            _ => format!("\x1b[{};2;{};{};{}m", if background { 48 } else { 38 }, r, g, b),
+        }
+    }
+    
     fn rgb_to_ansi16(&self, r: u8, g: u8, b: u8) -> u8 {
         // Simple conversion to 16-color ANSI
         let r_bright = r > 127;
@@ -822,3 +1631,36 @@ impl Display {
         }
     }
 }
+
+/// Quadrant glyph for a 2x2 mask (bits: top left, top right, bottom left, bottom right)
+fn quadrant_glyph(mask: u8) -> char {
+    const QUADRANTS: [char; 16] = [
+        ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+    ];
+    QUADRANTS[(mask & 0x0f) as usize]
+}
+
+/// Sextant glyph (Unicode 13 "Symbols for Legacy Computing") for a 2x3 mask in row-major order.
+/// The block skips the patterns that already exist as blank, left half, right half and full block.
+fn sextant_glyph(mask: u8) -> char {
+    match mask & 0x3f {
+        0 => ' ',
+        21 => '▌',
+        42 => '▐',
+        63 => '█',
+        mask => {
+            let index = mask as u32 - 1 - (mask > 21) as u32 - (mask > 42) as u32;
+            char::from_u32(0x1FB00 + index).unwrap_or(' ')
+        }
+    }
+}
+
+/// Braille pattern for a 2x4 mask in row-major order. Braille numbers its dots column by
+/// column (1-3 left, 4-6 right) and adds the bottom row as dots 7 and 8.
+fn braille_glyph(mask: u8) -> char {
+    const DOT_BITS: [u32; 8] = [0x01, 0x08, 0x02, 0x10, 0x04, 0x20, 0x40, 0x80];
+    let pattern = (0..8)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .fold(0, |pattern, bit| pattern | DOT_BITS[bit]);
+    char::from_u32(0x2800 + pattern).unwrap_or(' ')
+}
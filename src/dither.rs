@@ -0,0 +1,183 @@
+/// Dithering methods for the block renderer, selected with `dithering` in the config
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DitherMethod {
+    None,
+    FloydSteinberg,
+    Atkinson,
+    Bayer4,
+}
+
+impl DitherMethod {
+    pub fn from_config(name: &str) -> Self {
+        match name {
+            "floyd" | "floyd-steinberg" => DitherMethod::FloydSteinberg,
+            "atkinson" => DitherMethod::Atkinson,
+            "bayer4" | "bayer" => DitherMethod::Bayer4,
+            _ => DitherMethod::None,
+        }
+    }
+}
+
+/// 4x4 Bayer threshold matrix
+const BAYER4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Floyd-Steinberg: 7/16 right, 3/16 below left, 5/16 below, 1/16 below right
+const FLOYD_STEINBERG: &[(i32, i32, f32)] = &[(1, 0, 7.0 / 16.0), (-1, 1, 3.0 / 16.0), (0, 1, 5.0 / 16.0), (1, 1, 1.0 / 16.0)];
+
+/// Atkinson: 1/8 to six neighbours, only 3/4 of the error is kept which gives more contrast
+const ATKINSON: &[(i32, i32, f32)] = &[
+    (1, 0, 1.0 / 8.0),
+    (2, 0, 1.0 / 8.0),
+    (-1, 1, 1.0 / 8.0),
+    (0, 1, 1.0 / 8.0),
+    (1, 1, 1.0 / 8.0),
+    (0, 2, 1.0 / 8.0),
+];
+
+/// Error diffusion and ordered dithering over a grid of cells. Cells are visited in
+/// row-major order, one `apply` call per cell; for the error diffusion methods the
+/// quantization error is spread to the cells not visited yet.
+pub struct Ditherer {
+    method: DitherMethod,
+    width: usize,
+    height: usize,
+    errors: Vec<[f32; 3]>,
+    next_cell: usize,
+}
+
+impl Ditherer {
+    pub fn new(method: DitherMethod, width: u32, height: u32) -> Self {
+        let (width, height) = (width as usize, height as usize);
+        let errors = match method {
+            DitherMethod::FloydSteinberg | DitherMethod::Atkinson => vec![[0.0; 3]; width * height],
+            _ => Vec::new(),
+        };
+
+        Self { method, width, height, errors, next_cell: 0 }
+    }
+
+    /// Move past a cell that isn't drawn, leaving its error undistributed
+    pub fn skip(&mut self) {
+        self.next_cell += 1;
+    }
+
+    /// Quantize the value of the next cell. `quantize` maps a value to its result and the value
+    /// the result actually represents; `spread` is the distance between quantization levels,
+    /// which scales the Bayer offsets.
+    pub fn apply<T>(&mut self, value: [f32; 3], spread: f32, quantize: impl FnOnce([f32; 3]) -> (T, [f32; 3])) -> T {
+        let index = self.next_cell;
+        self.next_cell += 1;
+        let (x, y) = (index % self.width.max(1), index / self.width.max(1));
+
+        let kernel = match self.method {
+            DitherMethod::None => return quantize(value).0,
+            DitherMethod::Bayer4 => {
+                let offset = (BAYER4[y % 4][x % 4] as f32 + 0.5) / 16.0 - 0.5;
+                return quantize(value.map(|channel| channel + offset * spread)).0;
+            }
+            DitherMethod::FloydSteinberg => FLOYD_STEINBERG,
+            DitherMethod::Atkinson => ATKINSON,
+        };
+
+        let Some(error) = self.errors.get(index).copied() else {
+            return quantize(value).0;
+        };
+        let wanted = [value[0] + error[0], value[1] + error[1], value[2] + error[2]];
+        let (result, represented) = quantize(wanted);
+
+        for &(dx, dy, weight) in kernel {
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            if nx < 0 || nx >= self.width as i32 || ny >= self.height as i32 {
+                continue;
+            }
+            let neighbour = &mut self.errors[ny as usize * self.width + nx as usize];
+            for channel in 0..3 {
+                neighbour[channel] += (wanted[channel] - represented[channel]) * weight;
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Black or white per channel
+    fn threshold(value: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+        let result = value.map(|channel| if channel < 128.0 { 0.0 } else { 255.0 });
+        (value, result)
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "{} != {}", actual, expected);
+    }
+
+    /// Value each cell's quantizer was handed, in visiting order
+    fn dithered(method: DitherMethod, width: u32, height: u32, value: f32) -> Vec<f32> {
+        let mut ditherer = Ditherer::new(method, width, height);
+        (0..width * height).map(|_| ditherer.apply([value; 3], 255.0, threshold)[0]).collect()
+    }
+
+    #[test]
+    fn none_passes_values_through() {
+        assert_eq!(dithered(DitherMethod::None, 2, 2, 100.0), vec![100.0; 4]);
+    }
+
+    #[test]
+    fn floyd_steinberg_spreads_the_error_to_unvisited_neighbours() {
+        let mut ditherer = Ditherer::new(DitherMethod::FloydSteinberg, 3, 2);
+        assert_eq!(ditherer.apply([100.0; 3], 255.0, threshold)[0], 100.0);
+
+        // 7/16 of the first cell's error goes right, 3/16 below left, 5/16 below and 1/16 below right;
+        // the cells getting error stay black and pass all of it on
+        let [right, far_right, below, below_right, _] = [0; 5].map(|_| ditherer.apply([0.0; 3], 255.0, threshold)[0]);
+        assert_close(right, 100.0 * 7.0 / 16.0);
+        assert_close(far_right, right * 7.0 / 16.0);
+        assert_close(below, 100.0 * 5.0 / 16.0 + right * 3.0 / 16.0);
+        assert_close(below_right, 100.0 / 16.0 + right * 5.0 / 16.0 + far_right * 3.0 / 16.0 + below * 7.0 / 16.0);
+    }
+
+    #[test]
+    fn error_doesnt_wrap_to_the_next_row() {
+        let mut ditherer = Ditherer::new(DitherMethod::FloydSteinberg, 2, 2);
+        ditherer.skip();
+        // The last cell of the row has nothing to its right; its error must not reach the first cell below
+        ditherer.apply([100.0; 3], 255.0, threshold);
+        assert_eq!(ditherer.apply([0.0; 3], 255.0, threshold)[0], 100.0 * 3.0 / 16.0);
+    }
+
+    #[test]
+    fn skipped_cells_keep_their_error() {
+        let mut ditherer = Ditherer::new(DitherMethod::FloydSteinberg, 2, 1);
+        ditherer.skip();
+        assert_eq!(ditherer.apply([0.0; 3], 255.0, threshold)[0], 0.0);
+    }
+
+    #[test]
+    fn atkinson_drops_a_quarter_of_the_error() {
+        let mut ditherer = Ditherer::new(DitherMethod::Atkinson, 3, 1);
+        ditherer.apply([100.0; 3], 255.0, threshold);
+
+        // 1/8 each to the next two cells in the row, the other 6/8 would go to rows that don't exist
+        let right = ditherer.apply([0.0; 3], 255.0, threshold)[0];
+        assert_close(right, 100.0 / 8.0);
+        assert_close(ditherer.apply([0.0; 3], 255.0, threshold)[0], 100.0 / 8.0 + right / 8.0);
+    }
+
+    #[test]
+    fn bayer_offsets_depend_on_the_position_only() {
+        let values = dithered(DitherMethod::Bayer4, 4, 4, 100.0);
+        assert_eq!(values[0], 100.0 + (0.5 / 16.0 - 0.5) * 255.0);
+        assert_eq!(values[5], 100.0 + (4.5 / 16.0 - 0.5) * 255.0);
+        assert_eq!(dithered(DitherMethod::Bayer4, 4, 4, 100.0), values);
+    }
+
+    #[test]
+    fn method_names_from_the_config() {
+        assert_eq!(DitherMethod::from_config("floyd-steinberg"), DitherMethod::FloydSteinberg);
+        assert_eq!(DitherMethod::from_config("bayer"), DitherMethod::Bayer4);
+        assert_eq!(DitherMethod::from_config("unknown"), DitherMethod::None);
+    }
+}
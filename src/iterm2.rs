@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use image::GenericImageView;
+use std::env;
+use std::io::{Cursor, Write};
+use std::path::Path;
+
+/// iTerm2 inline image protocol (OSC 1337), also understood by WezTerm, Konsole and mintty
+pub struct Iterm2Graphics {
+    pub supports_iterm2: bool,
+    pub in_tmux: bool,
+}
+
+impl Iterm2Graphics {
+    pub fn new() -> Self {
+        let supports_iterm2 = Self::detect_iterm2_support();
+        let in_tmux = env::var("TMUX").is_ok();
+
+        Self {
+            supports_iterm2,
+            in_tmux,
+        }
+    }
+
+    /// Detect if the terminal supports the iTerm2 inline image protocol
+    fn detect_iterm2_support() -> bool {
+        if let Ok(term_program) = env::var("TERM_PROGRAM") {
+            match term_program.as_str() {
+                "iTerm.app" | "WezTerm" | "mintty" => return true,
+                _ => {}
+            }
+        }
+
+        // iTerm2 also sets LC_TERMINAL, which survives ssh when AcceptEnv LC_* is configured
+        if env::var("LC_TERMINAL").is_ok_and(|terminal| terminal == "iTerm2") {
+            return true;
+        }
+
+        // Konsole exports its version instead of TERM_PROGRAM
+        env::var("KONSOLE_VERSION").is_ok()
+    }
+
+    /// Render image inline, scaled to fit `width` x `height` cells while preserving aspect ratio
+    pub fn render_image(&self, image_path: &Path, width: u32, height: u32, cell_width: u32, cell_height: u32) -> Result<()> {
+        if !self.supports_iterm2 {
+            return Err(anyhow::anyhow!("Terminal doesn't support the iTerm2 inline image protocol"));
+        }
+
+        let max_width = width * cell_width;
+        let max_height = height * cell_height;
+
+        // SVGs are rasterized at the largest size that fits the cell area
+        if crate::svg::is_svg(image_path) {
+            let (orig_width, orig_height) = crate::svg::dimensions(image_path)?;
+            let scale = (max_width as f32 / orig_width as f32).min(max_height as f32 / orig_height as f32);
+            let rgba_img = crate::svg::rasterize(
+                image_path,
+                (orig_width as f32 * scale) as u32,
+                (orig_height as f32 * scale) as u32,
+            )?;
+            return self.send_image(image_path, image::DynamicImage::ImageRgba8(rgba_img), width, height);
+        }
+
+        let img = image::open(image_path)
+            .with_context(|| format!("Failed to open image: {}", image_path.display()))?;
+
+        // Downscale before sending so large source images don't produce megabytes of base64;
+        // the terminal does the final fit into the cell area
+        let (orig_width, orig_height) = img.dimensions();
+        let img = if orig_width > max_width || orig_height > max_height {
+            img.resize(max_width.max(1), max_height.max(1), image::imageops::FilterType::Lanczos3)
+        } else {
+            img
+        };
+
+        self.send_image(image_path, img, width, height)
+    }
+
+    /// Send the image as PNG, displayed in `width` x `height` cells
+    fn send_image(&self, image_path: &Path, img: image::DynamicImage, width: u32, height: u32) -> Result<()> {
+        let mut png = Cursor::new(Vec::new());
+        img.write_to(&mut png, image::ImageOutputFormat::Png)
+            .context("Failed to encode image as PNG")?;
+        let png = png.into_inner();
+
+        let name = image_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+
+        // width/height without a unit are cells; inline=1 displays instead of downloading
+        let sequence = format!(
+            "\x1b]1337;File=name={};size={};width={};height={};preserveAspectRatio=1;inline=1:{}\x07",
+            STANDARD.encode(name.as_bytes()),
+            png.len(),
+            width,
+            height,
+            STANDARD.encode(&png)
+        );
+
+        let mut output = Vec::new();
+        if self.in_tmux {
+            // tmux passthrough: wrap the sequence and double every ESC inside it
+            output.extend_from_slice(b"\x1bPtmux;");
+            for byte in sequence.bytes() {
+                if byte == 0x1b {
+                    output.push(0x1b);
+                }
+                output.push(byte);
+            }
+            output.extend_from_slice(b"\x1b\\");
+        } else {
+            output.extend_from_slice(sequence.as_bytes());
+        }
+
+        // Add newline for proper spacing
+        output.push(b'\n');
+
+        let mut stdout = std::io::stdout();
+        stdout.write_all(&output)?;
+        stdout.flush()?;
+
+        Ok(())
+    }
+}
+
+impl Default for Iterm2Graphics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -5,6 +5,8 @@ use std::env;
 use std::io::Write;
 use std::path::Path;
 
+use crate::animation::AnimationFrame;
+
 /// Kitty Graphics Protocol implementation for pixel-perfect image rendering
 pub struct KittyGraphics {
     pub supports_kitty: bool,
@@ -36,14 +38,8 @@ impl KittyGraphics {
             return true;
         }
         
-        // Check for other terminals that might support it
-        if let Ok(term_program) = env::var("TERM_PROGRAM") {
-            match term_program.as_str() {
-                "iTerm.app" => return true,
-                "WezTerm" => return true,
-                _ => {}
-            }
-        }
+        // iTerm2 doesn't implement the protocol and WezTerm only with enable_kitty_graphics,
+        // both get the iTerm2 inline image protocol instead
         
         false
     }
@@ -91,12 +87,18 @@ impl KittyGraphics {
             return Err(anyhow::anyhow!("Terminal doesn't support Kitty Graphics Protocol"));
         }
 
-        // Load and process image
-        let img = image::open(image_path)
-            .with_context(|| format!("Failed to open image: {}", image_path.display()))?;
+        // Load and process image; SVGs are rasterized at the final size instead of resized
+        let img = if crate::svg::is_svg(image_path) {
+            None
+        } else {
+            Some(image::open(image_path).with_context(|| format!("Failed to open image: {}", image_path.display()))?)
+        };
 
         // Get original image dimensions
-        let (orig_width, orig_height) = img.dimensions();
+        let (orig_width, orig_height) = match &img {
+            Some(img) => img.dimensions(),
+            None => crate::svg::dimensions(image_path)?,
+        };
         let aspect_ratio = orig_width as f32 / orig_height as f32;
         
         // Calculate target pixel dimensions while preserving aspect ratio
@@ -115,29 +117,81 @@ impl KittyGraphics {
             ((target_pixel_height as f32 * aspect_ratio) as u32, target_pixel_height)
         };
 
-        // Resize image preserving aspect ratio
-        let resized = img.resize(
-            final_width,
-            final_height,
-            image::imageops::FilterType::Lanczos3,
-        );
-
-        // Convert to RGBA format
-        let rgba_img = resized.to_rgba8();
+        // Resize image preserving aspect ratio and convert to RGBA format
+        let rgba_img = match img {
+            Some(img) => img.resize(final_width, final_height, image::imageops::FilterType::Lanczos3).to_rgba8(),
+            None => crate::svg::rasterize(image_path, final_width, final_height)?,
+        };
         let raw_data = rgba_img.as_raw();
 
-        // Encode as base64
-        let base64_data = STANDARD.encode(raw_data);
+        let mut output = Vec::new();
+        let control = format!("a=T,f=32,s={},v={},c={},r={}", rgba_img.width(), rgba_img.height(), width, height);
+        self.push_chunked(&mut output, &control, raw_data)?;
 
-        // Send in chunks (Kitty protocol supports up to 4096 bytes per chunk)
-        const CHUNK_SIZE: usize = 4096;
-        let chunks: Vec<&str> = base64_data
-            .as_bytes()
-            .chunks(CHUNK_SIZE)
-            .map(|chunk| std::str::from_utf8(chunk).unwrap())
-            .collect();
+        // Add newline for proper spacing
+        output.push(b'\n');
 
+        print!("{}", String::from_utf8_lossy(&output));
+        std::io::stdout().flush()?;
+
+        Ok(())
+    }
+
+    /// Render an animated image. The first frame is displayed like in Standard Mode, the others
+    /// are added as animation frames that the terminal plays on its own, so nothing waits for it.
+    /// `loops` = 0 plays forever, otherwise the animation stops on its last frame.
+    pub fn render_animation(&self, frames: &[AnimationFrame], width: u32, height: u32, cell_width: u32, cell_height: u32, loops: u32) -> Result<()> {
+        if !self.supports_kitty {
+            return Err(anyhow::anyhow!("Terminal doesn't support Kitty Graphics Protocol"));
+        }
+        let first = frames.first().context("Animation has no frames")?;
+
+        // c and r stretch the image, so fit them to the animation's aspect ratio
+        let (orig_width, orig_height) = first.image.dimensions();
+        let (columns, rows) = crate::terminal::fit_cells(orig_width, orig_height, width, height, (cell_width, cell_height));
+        let (final_width, final_height) = ((columns * cell_width).max(1), (rows * cell_height).max(1));
+
+        // Frames are addressed through an image id; q=2 keeps the terminal from answering
+        let id = std::process::id();
         let mut output = Vec::new();
+        for (index, frame) in frames.iter().enumerate() {
+            let resized = image::imageops::resize(&frame.image, final_width, final_height, image::imageops::FilterType::Lanczos3);
+            let control = if index == 0 {
+                format!("a=T,i={},q=2,f=32,s={},v={},c={},r={}", id, final_width, final_height, columns, rows)
+            } else {
+                format!("a=f,i={},q=2,f=32,s={},v={},z={}", id, final_width, final_height, frame.delay_ms)
+            };
+            self.push_chunked(&mut output, &control, resized.as_raw())?;
+        }
+
+        // The first frame's gap has to be set separately, then the animation is started.
+        // v = 1 loops forever, v = n + 1 plays n loops.
+        self.push_chunked(&mut output, &format!("a=a,i={},q=2,r=1,z={}", id, first.delay_ms), &[])?;
+        self.push_chunked(&mut output, &format!("a=a,i={},q=2,s=3,v={}", id, loops.saturating_add(1)), &[])?;
+
+        // Add newline for proper spacing
+        output.push(b'\n');
+
+        print!("{}", String::from_utf8_lossy(&output));
+        std::io::stdout().flush()?;
+
+        Ok(())
+    }
+
+    /// Append a graphics command, sending the payload base64 encoded in chunks
+    /// (Kitty protocol supports up to 4096 bytes per chunk)
+    fn push_chunked(&self, output: &mut Vec<u8>, control: &str, data: &[u8]) -> Result<()> {
+        const CHUNK_SIZE: usize = 4096;
+        let base64_data = STANDARD.encode(data);
+        let chunks: Vec<&str> = if base64_data.is_empty() {
+            vec![""]
+        } else {
+            base64_data
+                .as_bytes()
+                .chunks(CHUNK_SIZE)
+                .map(|chunk| std::str::from_utf8(chunk).unwrap())
+                .collect()
+        };
 
         for (i, chunk) in chunks.iter().enumerate() {
             if self.in_tmux {
@@ -146,17 +200,13 @@ impl KittyGraphics {
 
             if i == 0 {
                 // First chunk: include image parameters
-                write!(
-                    &mut output,
-                    "\x1b_Ga=T,f=32,s={},v={},c={},r={}",
-                    final_width, final_height, width, height
-                )?;
+                write!(output, "\x1b_G{}", control)?;
                 
                 if chunks.len() > 1 {
                     output.extend_from_slice(b",m=1"); // More chunks follow
                 }
                 
-                write!(&mut output, ";{}\x1b\\", chunk)?;
+                write!(output, ";{}\x1b\\", chunk)?;
             } else {
                 // Continuation chunks
                 output.extend_from_slice(b"\x1b_G");
@@ -165,7 +215,7 @@ impl KittyGraphics {
                 } else {
                     output.extend_from_slice(b"m=0"); // Last chunk
                 }
-                write!(&mut output, ";{}\x1b\\", chunk)?;
+                write!(output, ";{}\x1b\\", chunk)?;
             }
 
             if self.in_tmux {
@@ -173,12 +223,6 @@ impl KittyGraphics {
             }
         }
 
-        // Add newline for proper spacing
-        output.push(b'\n');
-
-        print!("{}", String::from_utf8_lossy(&output));
-        std::io::stdout().flush()?;
-
         Ok(())
     }
 
@@ -188,8 +232,22 @@ impl KittyGraphics {
             return Err(anyhow::anyhow!("Terminal doesn't support Kitty Graphics Protocol"));
         }
 
+        // The terminal can't decode SVGs itself, they are rasterized in Standard Mode
+        if crate::svg::is_svg(image_path) {
+            return self.render_image_standard(image_path, width, height, cell_width, cell_height);
+        }
+
+        // Direct Mode stretches the image over exactly c x r cells, so shrink the area
+        // to the image's aspect ratio first
+        let (columns, rows) = match image::image_dimensions(image_path) {
+            Ok((image_width, image_height)) => {
+                crate::terminal::fit_cells(image_width, image_height, width, height, (cell_width, cell_height))
+            }
+            Err(_) => (width, height),
+        };
+
         // Try Direct Mode first (faster and more efficient)
-        if let Ok(()) = self.render_image_direct(image_path, width, height) {
+        if let Ok(()) = self.render_image_direct(image_path, columns, rows) {
             return Ok(());
         }
 
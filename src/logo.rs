@@ -0,0 +1,359 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{expand_tilde, DisplayConfig, ImageScheduleEntry};
+use crate::system_info::SystemInfo;
+
+/// File types the image renderers can show
+const LOGO_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "svgz"];
+
+/// Last logo shown per rotation, for `image_rotation = "round-robin"`
+const ROTATION_FILE: &str = "rotation.json";
+
+/// Find the logo to show: `image_path` if it matches anything, otherwise a `logo_map` entry or
+/// a logo in the pngs directory matching the OS or the compositor. None if nothing matches, in
+/// which case the text logo takes over.
+pub fn resolve(config: &DisplayConfig) -> Option<PathBuf> {
+    // image_path may be a single file, a directory or a glob
+    if let Some(image_path) = &config.image_path {
+        let candidates = image_candidates(&expand_tilde(image_path));
+        if !candidates.is_empty() {
+            return Some(pick(candidates, &format!("image_path:{}", image_path.display()), config));
+        }
+    }
+
+    let keys = logo_keys();
+
+    // Explicit mappings win over file name matches
+    for key in &keys {
+        if let Some(logos) = config.logo_map.get(key) {
+            let mut existing: Vec<PathBuf> =
+                logos.paths().iter().map(|path| expand_tilde(path)).filter(|path| path.exists()).collect();
+            existing.sort();
+            existing.dedup();
+            if !existing.is_empty() {
+                return Some(pick(existing, &format!("logo_map:{}", key), config));
+            }
+        }
+    }
+
+    let pngs_dir = dirs::config_dir()?.join("hyprgreetr").join("pngs");
+    let available = logo_files(&pngs_dir);
+    for key in &keys {
+        let matching: Vec<PathBuf> =
+            available.iter().filter(|path| file_matches(path, key)).cloned().collect();
+        if !matching.is_empty() {
+            return Some(pick(matching, &format!("pngs:{}", key), config));
+        }
+    }
+
+    None
+}
+
+/// Lowercase names to look logos up by, most specific first: the os-release `ID` and `ID_LIKE`
+/// entries, then the compositor and the desktops from `XDG_CURRENT_DESKTOP`
+fn logo_keys() -> Vec<String> {
+    let mut keys = SystemInfo::os_release_ids();
+    keys.extend(crate::compositor::detect_name().map(str::to_lowercase));
+    if let Ok(desktops) = std::env::var("XDG_CURRENT_DESKTOP") {
+        keys.extend(desktops.split(':').map(str::to_lowercase));
+    }
+
+    let mut unique = Vec::new();
+    for key in keys {
+        if !key.is_empty() && !unique.contains(&key) {
+            unique.push(key);
+        }
+    }
+    unique
+}
+
+/// Image files directly inside `dir`, sorted by name
+fn logo_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|extension| LOGO_EXTENSIONS.contains(&extension.to_string_lossy().to_lowercase().as_str()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Whether the file name starts with the key, ignoring case and separators, so "cachyos" finds
+/// both `cachyos_logo1.png` and `Cachy_OS.png`. For keys like "opensuse-tumbleweed" the last
+/// part alone also matches (`tumbleweed_logo.png`).
+fn file_matches(path: &Path, key: &str) -> bool {
+    let normalize = |text: &str| -> String {
+        text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+    };
+
+    let Some(stem) = path.file_stem() else {
+        return false;
+    };
+    let stem = normalize(&stem.to_string_lossy());
+
+    let mut prefixes = vec![normalize(key)];
+    if let Some((_, last)) = key.rsplit_once('-') {
+        prefixes.push(normalize(last));
+    }
+    prefixes.iter().any(|prefix| !prefix.is_empty() && stem.starts_with(prefix.as_str()))
+}
+
+/// Files `image_path` stands for: the image itself, the images in a directory, or the files
+/// matching a glob like `~/.config/hyprgreetr/pngs/*.png`, sorted by name
+fn image_candidates(path: &Path) -> Vec<PathBuf> {
+    if path.is_dir() {
+        return logo_files(path);
+    }
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+
+    let pattern = path.to_string_lossy();
+    if !pattern.contains(['*', '?', '[']) {
+        return Vec::new();
+    }
+    let Ok(paths) = glob::glob(&pattern) else {
+        eprintln!("[Warning] Invalid image_path pattern: {}", pattern);
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = paths.filter_map(Result::ok).filter(|path| path.is_file()).collect();
+    files.sort();
+    files
+}
+
+/// Pick one of several matching logos according to `image_rotation`. `rotation_key` tells the
+/// sets of logos apart in the round-robin state.
+fn pick(mut candidates: Vec<PathBuf>, rotation_key: &str, config: &DisplayConfig) -> PathBuf {
+    if candidates.len() == 1 {
+        return candidates.remove(0);
+    }
+
+    match config.image_rotation.as_str() {
+        "round-robin" => pick_next(candidates, rotation_key),
+        "schedule" => {
+            // Outside of all scheduled times every logo can show up
+            let scheduled = scheduled_candidates(&candidates, &config.image_schedule);
+            pick_random(if scheduled.is_empty() { candidates } else { scheduled })
+        }
+        _ => pick_random(candidates),
+    }
+}
+
+/// A different logo on each launch
+fn pick_random(mut candidates: Vec<PathBuf>) -> PathBuf {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    // Seed from the current time like the MOTD rotation does
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    let mut hasher = DefaultHasher::new();
+    now.hash(&mut hasher);
+
+    let index = (hasher.finish() as usize) % candidates.len();
+    candidates.swap_remove(index)
+}
+
+/// The logo after the one shown last time, in file name order. Going by name rather than by
+/// position keeps the order stable when logos are added or removed in between.
+fn pick_next(mut candidates: Vec<PathBuf>, rotation_key: &str) -> PathBuf {
+    let state_path = dirs::cache_dir().map(|dir| dir.join("hyprgreetr").join(ROTATION_FILE));
+    let mut state: BTreeMap<String, PathBuf> = state_path
+        .as_deref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let index = state
+        .get(rotation_key)
+        .and_then(|last| candidates.iter().position(|candidate| candidate > last))
+        .unwrap_or(0);
+    let next = candidates.swap_remove(index);
+
+    if let Some(path) = state_path {
+        state.insert(rotation_key.to_string(), next.clone());
+        save_state(&path, &state);
+    }
+    next
+}
+
+fn save_state(path: &Path, state: &BTreeMap<String, PathBuf>) {
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(content) = serde_json::to_string(state) {
+        // Write to a temporary file first so concurrently starting shells never read a partial file
+        let temp_path = path.with_extension(format!("json.{}", std::process::id()));
+        if fs::write(&temp_path, content).is_ok() {
+            let _ = fs::rename(&temp_path, path);
+        }
+    }
+}
+
+/// The candidates whose file name matches the first schedule entry covering the current
+/// local time, empty when no entry does
+fn scheduled_candidates(candidates: &[PathBuf], schedule: &[ImageScheduleEntry]) -> Vec<PathBuf> {
+    let Some((weekday, minute)) = local_time() else {
+        return Vec::new();
+    };
+
+    let Some(entry) = schedule.iter().find(|entry| schedule_matches(entry, weekday, minute)) else {
+        return Vec::new();
+    };
+    let Ok(pattern) = glob::Pattern::new(&entry.image) else {
+        eprintln!("[Warning] Invalid image pattern in image_schedule: {}", entry.image);
+        return Vec::new();
+    };
+
+    candidates
+        .iter()
+        .filter(|candidate| {
+            candidate.file_name().is_some_and(|name| pattern.matches(&name.to_string_lossy()))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Whether the entry covers the weekday (0 = Sunday) and minute of the day
+fn schedule_matches(entry: &ImageScheduleEntry, weekday: u32, minute: u32) -> bool {
+    const DAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+    let today = DAYS[weekday as usize % 7];
+    if !entry.days.is_empty() && !entry.days.iter().any(|day| day.to_lowercase().starts_with(today)) {
+        return false;
+    }
+
+    let parse = |time: &Option<String>, default: u32| -> Option<u32> {
+        let Some(time) = time else {
+            return Some(default);
+        };
+        let (hours, minutes) = time.split_once(':')?;
+        let (hours, minutes) = (hours.trim().parse::<u32>().ok()?, minutes.trim().parse::<u32>().ok()?);
+        (hours <= 24 && minutes < 60).then_some(hours * 60 + minutes)
+    };
+    let (Some(from), Some(to)) = (parse(&entry.from, 0), parse(&entry.to, 24 * 60)) else {
+        eprintln!("[Warning] Invalid time in image_schedule entry for {}, expected \"HH:MM\"", entry.image);
+        return false;
+    };
+
+    if from <= to {
+        (from..to).contains(&minute)
+    } else {
+        // e.g. from = "22:00", to = "06:00"
+        minute >= from || minute < to
+    }
+}
+
+/// Local weekday (0 = Sunday) and minute of the day
+fn local_time() -> Option<(u32, u32)> {
+    // SAFETY: time() with a null pointer only returns the time; localtime_r writes into the
+    // zeroed tm we own and returns null on failure
+    unsafe {
+        let now = libc::time(std::ptr::null_mut());
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&now, &mut tm).is_null() {
+            return None;
+        }
+        Some((tm.tm_wday as u32, (tm.tm_hour * 60 + tm.tm_min) as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names_match_ignoring_case_and_separators() {
+        assert!(file_matches(Path::new("pngs/cachyos_logo1.png"), "cachyos"));
+        assert!(file_matches(Path::new("pngs/Cachy_OS.png"), "cachyos"));
+        assert!(file_matches(Path::new("pngs/Arch-Linux.svg"), "archlinux"));
+        assert!(!file_matches(Path::new("pngs/debian.png"), "cachyos"));
+    }
+
+    #[test]
+    fn only_the_start_of_the_name_matches() {
+        assert!(!file_matches(Path::new("pngs/my_arch.png"), "arch"));
+        assert!(!file_matches(Path::new("pngs/arc.png"), "arch"));
+    }
+
+    #[test]
+    fn last_part_of_a_dashed_key_matches_alone() {
+        assert!(file_matches(Path::new("pngs/opensuse-tumbleweed.png"), "opensuse-tumbleweed"));
+        assert!(file_matches(Path::new("pngs/tumbleweed_logo.png"), "opensuse-tumbleweed"));
+        assert!(!file_matches(Path::new("pngs/leap.png"), "opensuse-tumbleweed"));
+    }
+
+    #[test]
+    fn empty_keys_match_nothing() {
+        assert!(!file_matches(Path::new("pngs/arch.png"), ""));
+        assert!(!file_matches(Path::new("pngs/arch.png"), "-"));
+    }
+
+    fn entry(days: &[&str], from: Option<&str>, to: Option<&str>) -> ImageScheduleEntry {
+        ImageScheduleEntry {
+            days: days.iter().map(|day| day.to_string()).collect(),
+            from: from.map(str::to_string),
+            to: to.map(str::to_string),
+            image: "night_*".to_string(),
+        }
+    }
+
+    const fn at(hours: u32, minutes: u32) -> u32 {
+        hours * 60 + minutes
+    }
+
+    #[test]
+    fn time_range_includes_start_and_excludes_end() {
+        let entry = entry(&[], Some("08:00"), Some("17:30"));
+        assert!(schedule_matches(&entry, 1, at(8, 0)));
+        assert!(schedule_matches(&entry, 1, at(17, 29)));
+        assert!(!schedule_matches(&entry, 1, at(17, 30)));
+        assert!(!schedule_matches(&entry, 1, at(7, 59)));
+    }
+
+    #[test]
+    fn time_range_wraps_past_midnight() {
+        let entry = entry(&[], Some("22:00"), Some("06:00"));
+        assert!(schedule_matches(&entry, 3, at(22, 0)));
+        assert!(schedule_matches(&entry, 3, at(23, 59)));
+        assert!(schedule_matches(&entry, 3, at(0, 0)));
+        assert!(schedule_matches(&entry, 3, at(5, 59)));
+        assert!(!schedule_matches(&entry, 3, at(6, 0)));
+        assert!(!schedule_matches(&entry, 3, at(12, 0)));
+    }
+
+    #[test]
+    fn missing_times_cover_the_whole_day() {
+        assert!(schedule_matches(&entry(&[], None, None), 0, at(0, 0)));
+        assert!(schedule_matches(&entry(&[], None, None), 0, at(23, 59)));
+        assert!(schedule_matches(&entry(&[], None, Some("12:00")), 0, at(0, 0)));
+        assert!(!schedule_matches(&entry(&[], None, Some("12:00")), 0, at(12, 0)));
+        assert!(schedule_matches(&entry(&[], Some("12:00"), Some("24:00")), 0, at(23, 59)));
+    }
+
+    #[test]
+    fn days_are_matched_by_prefix() {
+        let weekend = entry(&["Sat", "sunday"], None, None);
+        assert!(schedule_matches(&weekend, 0, at(12, 0)));
+        assert!(schedule_matches(&weekend, 6, at(12, 0)));
+        assert!(!schedule_matches(&weekend, 1, at(12, 0)));
+    }
+
+    #[test]
+    fn invalid_times_never_match() {
+        assert!(!schedule_matches(&entry(&[], Some("25:00"), None), 0, at(12, 0)));
+        assert!(!schedule_matches(&entry(&[], Some("noon"), None), 0, at(12, 0)));
+        assert!(!schedule_matches(&entry(&[], None, Some("12:60")), 0, at(1, 0)));
+    }
+}
@@ -1,16 +1,38 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use std::path::PathBuf;
 
 mod config;
 mod system_info;
+mod cache;
+mod compositor;
+mod pci_ids;
 mod display;
 mod kitty_graphics;
+mod sixel;
+mod iterm2;
+mod terminal;
+mod dither;
+mod animation;
+mod svg;
+mod ascii_logo;
+mod logo;
 
 use config::{Config, MotdConfig, expand_tilde};
 use system_info::SystemInfo;
 use display::Display;
 
+/// Output format for the gathered system information
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Render the greeter (image, modules and MOTD)
+    Text,
+    /// Print the gathered modules as JSON
+    Json,
+    /// Print the gathered modules as TOML
+    Toml,
+}
+
 #[derive(Parser)]
 #[command(name = "hyprgreetr")]
 #[command(about = "A configurable system information tool")]
@@ -26,6 +48,10 @@ struct Cli {
     /// Disable image display
     #[arg(long)]
     no_image: bool,
+    
+    /// Output format (json and toml print machine-readable module data)
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
 }
 
 fn main() -> Result<()> {
@@ -48,6 +74,19 @@ fn main() -> Result<()> {
     }
     
     let system_info = SystemInfo::gather_with_config(&config);
+    
+    match cli.format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&system_info)?);
+            return Ok(());
+        }
+        OutputFormat::Toml => {
+            print!("{}", toml::to_string_pretty(&system_info)?);
+            return Ok(());
+        }
+        OutputFormat::Text => {}
+    }
+    
     let display = Display::new(config, !cli.no_image);
     
     display.show(&system_info)?;
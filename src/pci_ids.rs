@@ -0,0 +1,67 @@
+use std::fs;
+use std::sync::OnceLock;
+
+/// Locations of the pci.ids database used by pciutils/hwdata on common distributions
+const PCI_IDS_PATHS: &[&str] = &[
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+    "/usr/share/pciids/pci.ids",
+];
+
+/// Marketing name for a PCI device, e.g. "NVIDIA GeForce RTX 4090" for 10de:2684
+pub fn device_name(vendor_id: u16, device_id: u16) -> Option<String> {
+    static DATABASE: OnceLock<Option<String>> = OnceLock::new();
+    let database = DATABASE
+        .get_or_init(|| PCI_IDS_PATHS.iter().find_map(|path| fs::read_to_string(path).ok()))
+        .as_deref()?;
+    let (vendor, device) = lookup(database, vendor_id, device_id)?;
+
+    let vendor = short_vendor_name(vendor_id, vendor);
+    let device = device.map(marketing_name)?;
+    if device.starts_with(&vendor) {
+        Some(device.to_string())
+    } else {
+        Some(format!("{} {}", vendor, device))
+    }
+}
+
+/// Find the vendor and device entries in pci.ids. Vendors start at column 0,
+/// their devices follow on lines indented by one tab (subsystems use two).
+fn lookup(database: &str, vendor_id: u16, device_id: u16) -> Option<(&str, Option<&str>)> {
+    let vendor_prefix = format!("{:04x}  ", vendor_id);
+    let device_prefix = format!("\t{:04x}  ", device_id);
+
+    let mut lines = database.lines().skip_while(|line| !line.starts_with(&vendor_prefix));
+    let vendor = lines.next()?[vendor_prefix.len()..].trim();
+
+    let device = lines
+        .take_while(|line| line.starts_with('\t') || line.starts_with('#') || line.is_empty())
+        .find_map(|line| line.strip_prefix(&device_prefix))
+        .map(str::trim);
+
+    Some((vendor, device))
+}
+
+/// Vendor names as users know them rather than the registered company names
+fn short_vendor_name(vendor_id: u16, vendor: &str) -> String {
+    match vendor_id {
+        0x10de => "NVIDIA".to_string(),
+        0x1002 | 0x1022 => "AMD".to_string(),
+        0x8086 => "Intel".to_string(),
+        _ => vendor
+            .trim_end_matches(", Inc.")
+            .trim_end_matches(" Inc.")
+            .trim_end_matches(" Corporation")
+            .trim_end_matches(" Corp.")
+            .to_string(),
+    }
+}
+
+/// Device entries look like "AD102 [GeForce RTX 4090]"; the bracketed part is the product name
+fn marketing_name(device: &str) -> &str {
+    match (device.find('['), device.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &device[start + 1..end],
+        _ => device,
+    }
+}
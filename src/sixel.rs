@@ -0,0 +1,257 @@
+use anyhow::{Context, Result};
+use color_quant::NeuQuant;
+use image::{GenericImageView, RgbaImage};
+use std::env;
+use std::io::Write;
+use std::path::Path;
+
+/// Sixel graphics implementation for terminals without the Kitty Graphics Protocol (foot, Konsole, mlterm, ...)
+pub struct SixelGraphics {
+    pub supports_sixel: bool,
+    pub in_tmux: bool,
+}
+
+impl SixelGraphics {
+    pub fn new() -> Self {
+        let supports_sixel = Self::detect_sixel_support();
+        let in_tmux = env::var("TMUX").is_ok();
+
+        Self {
+            supports_sixel,
+            in_tmux,
+        }
+    }
+
+    /// Detect if the terminal supports Sixel graphics
+    fn detect_sixel_support() -> bool {
+        // Terminals that identify themselves through TERM
+        if let Ok(term) = env::var("TERM")
+            && (term.starts_with("foot") || term.contains("mlterm") || term.contains("yaft"))
+        {
+            return true;
+        }
+
+        // Konsole (22.04+) and Windows Terminal (1.22+) export their own variables
+        if env::var("KONSOLE_VERSION").is_ok() || env::var("WT_SESSION").is_ok() {
+            return true;
+        }
+
+        if let Ok(term_program) = env::var("TERM_PROGRAM") {
+            match term_program.as_str() {
+                "WezTerm" | "mintty" | "contour" => return true,
+                _ => {}
+            }
+        }
+
+        false
+    }
+
+    /// Render image as Sixel, scaled to fit `width` x `height` cells while preserving aspect ratio
+    pub fn render_image(&self, image_path: &Path, width: u32, height: u32, cell_width: u32, cell_height: u32) -> Result<()> {
+        if !self.supports_sixel {
+            return Err(anyhow::anyhow!("Terminal doesn't support Sixel graphics"));
+        }
+
+        // SVGs are rasterized at the final size instead of resized
+        let img = if crate::svg::is_svg(image_path) {
+            None
+        } else {
+            Some(image::open(image_path).with_context(|| format!("Failed to open image: {}", image_path.display()))?)
+        };
+
+        // Fit the image into the cell area, like the Kitty standard mode does
+        let (orig_width, orig_height) = match &img {
+            Some(img) => img.dimensions(),
+            None => crate::svg::dimensions(image_path)?,
+        };
+        let aspect_ratio = orig_width as f32 / orig_height as f32;
+        let target_pixel_width = width * cell_width;
+        let target_pixel_height = height * cell_height;
+        let target_aspect_ratio = target_pixel_width as f32 / target_pixel_height as f32;
+
+        let (final_width, final_height) = if aspect_ratio > target_aspect_ratio {
+            (target_pixel_width, (target_pixel_width as f32 / aspect_ratio) as u32)
+        } else {
+            ((target_pixel_height as f32 * aspect_ratio) as u32, target_pixel_height)
+        };
+
+        let resized = match img {
+            Some(img) => img
+                .resize(final_width.max(1), final_height.max(1), image::imageops::FilterType::Lanczos3)
+                .to_rgba8(),
+            None => crate::svg::rasterize(image_path, final_width, final_height)?,
+        };
+
+        let sixel = Self::encode(&resized);
+
+        let mut output = Vec::new();
+        if self.in_tmux {
+            // tmux passthrough: wrap the sequence and double every ESC inside it
+            output.extend_from_slice(b"\x1bPtmux;");
+            for byte in sixel.bytes() {
+                if byte == 0x1b {
+                    output.push(0x1b);
+                }
+                output.push(byte);
+            }
+            output.extend_from_slice(b"\x1b\\");
+        } else {
+            output.extend_from_slice(sixel.as_bytes());
+        }
+
+        // Add newline for proper spacing
+        output.push(b'\n');
+
+        let mut stdout = std::io::stdout();
+        stdout.write_all(&output)?;
+        stdout.flush()?;
+
+        Ok(())
+    }
+
+    /// Encode an RGBA image as a Sixel sequence: a palette of up to 256 colors, then
+    /// bands of six pixel rows where each color's pixels are run-length encoded.
+    /// Transparent pixels are left unpainted so the terminal background shows through.
+    fn encode(image: &RgbaImage) -> String {
+        let (width, height) = image.dimensions();
+        let pixels = image.as_raw();
+
+        let quantizer = NeuQuant::new(10, 256, pixels);
+        let palette = quantizer.color_map_rgb();
+
+        // Palette index per pixel, None for transparent pixels
+        let indices: Vec<Option<usize>> = pixels
+            .chunks_exact(4)
+            .map(|pixel| (pixel[3] >= 128).then(|| quantizer.index_of(pixel)))
+            .collect();
+
+        // DCS P1=0 (default aspect), P2=1 (unset pixels stay transparent); raster attributes give the size
+        let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+
+        for (index, rgb) in palette.chunks_exact(3).enumerate() {
+            let percent = |value: u8| (value as u32 * 100 + 127) / 255;
+            out.push_str(&format!("#{};2;{};{};{}", index, percent(rgb[0]), percent(rgb[1]), percent(rgb[2])));
+        }
+
+        let color_count = palette.len() / 3;
+        let width = width as usize;
+        let height = height as usize;
+
+        for band_top in (0..height).step_by(6) {
+            let band_rows = (height - band_top).min(6);
+
+            // Sixel bit masks per color for every column in this band
+            let mut masks = vec![vec![0u8; width]; color_count];
+            let mut used = vec![false; color_count];
+            for row in 0..band_rows {
+                for x in 0..width {
+                    if let Some(color) = indices[(band_top + row) * width + x] {
+                        masks[color][x] |= 1 << row;
+                        used[color] = true;
+                    }
+                }
+            }
+
+            let mut first = true;
+            for color in (0..color_count).filter(|&color| used[color]) {
+                // "$" returns to the start of the band so the next color overlays this one
+                if !first {
+                    out.push('$');
+                }
+                first = false;
+
+                out.push_str(&format!("#{}", color));
+                Self::push_run_length_encoded(&mut out, &masks[color]);
+            }
+
+            // "-" moves down to the next band
+            out.push('-');
+        }
+
+        out.push_str("\x1b\\");
+        out
+    }
+
+    /// Append sixel characters for one color row, compressing repeats as "!<count><char>"
+    fn push_run_length_encoded(out: &mut String, masks: &[u8]) {
+        // Trailing empty columns don't need to be sent
+        let length = masks.iter().rposition(|&mask| mask != 0).map_or(0, |last| last + 1);
+
+        let mut x = 0;
+        while x < length {
+            let mask = masks[x];
+            let run = masks[x..length].iter().take_while(|&&other| other == mask).count();
+            let character = (63 + mask) as char;
+
+            if run > 3 {
+                out.push_str(&format!("!{}{}", run, character));
+            } else {
+                for _ in 0..run {
+                    out.push(character);
+                }
+            }
+            x += run;
+        }
+    }
+}
+
+impl Default for SixelGraphics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_length_encoded(masks: &[u8]) -> String {
+        let mut out = String::new();
+        SixelGraphics::push_run_length_encoded(&mut out, masks);
+        out
+    }
+
+    #[test]
+    fn short_runs_are_written_out() {
+        // 63 + mask: 1 -> '@', 2 -> 'A', 63 -> '~'
+        assert_eq!(run_length_encoded(&[1, 1, 1, 2, 63]), "@@@A~");
+    }
+
+    #[test]
+    fn long_runs_are_compressed() {
+        assert_eq!(run_length_encoded(&[1, 1, 1, 1, 2, 2, 2, 2, 2]), "!4@!5A");
+    }
+
+    #[test]
+    fn trailing_empty_columns_are_dropped() {
+        assert_eq!(run_length_encoded(&[0, 0, 1, 0, 0, 0, 0]), "??@");
+        assert_eq!(run_length_encoded(&[0, 0, 0]), "");
+    }
+
+    #[test]
+    fn encode_writes_one_band_per_six_rows() {
+        let image = RgbaImage::from_pixel(3, 7, image::Rgba([255, 0, 0, 255]));
+        let sixel = SixelGraphics::encode(&image);
+
+        assert!(sixel.starts_with("\x1bP0;1;0q\"1;1;3;7#0;2;"), "{:?}", sixel);
+        assert!(sixel.ends_with("-\x1b\\"), "{:?}", sixel);
+        // A full band of six rows, then a band with only the top row set
+        assert!(sixel.contains("~~~-"), "{:?}", sixel);
+        assert!(sixel.contains("@@@-"), "{:?}", sixel);
+        assert_eq!(sixel.matches('-').count(), 2);
+    }
+
+    #[test]
+    fn encode_leaves_transparent_pixels_unpainted() {
+        let image = RgbaImage::from_pixel(4, 6, image::Rgba([255, 255, 255, 0]));
+        let sixel = SixelGraphics::encode(&image);
+
+        // Every "#" starts a palette definition ("#n;2;r;g;b"), none selects a color to draw with
+        let selects_color = sixel
+            .split('#')
+            .skip(1)
+            .any(|part| !part.trim_start_matches(|c: char| c.is_ascii_digit()).starts_with(';'));
+        assert!(!selects_color, "{:?}", sixel);
+        assert!(sixel.ends_with("-\x1b\\"), "{:?}", sixel);
+    }
+}
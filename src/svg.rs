@@ -0,0 +1,55 @@
+use anyhow::{Context, Result};
+use image::RgbaImage;
+use resvg::{tiny_skia, usvg};
+use std::path::Path;
+
+/// Whether the image is an SVG, which the `image` crate can't decode and which is rasterized
+/// at the exact size it is displayed at instead
+pub fn is_svg(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("svg") || extension.eq_ignore_ascii_case("svgz"))
+}
+
+fn load_tree(path: &Path) -> Result<usvg::Tree> {
+    let data = std::fs::read(path).with_context(|| format!("Failed to open image: {}", path.display()))?;
+
+    let mut options = usvg::Options {
+        resources_dir: path.parent().map(Path::to_path_buf),
+        ..Default::default()
+    };
+    // Loading the system fonts takes a while, only do it for logos with text in them
+    // (compressed .svgz files can't be checked and always get them)
+    if data.windows(5).any(|window| window == b"<text") || !data.starts_with(b"<") {
+        options.fontdb_mut().load_system_fonts();
+    }
+
+    usvg::Tree::from_data(&data, &options).with_context(|| format!("Failed to parse SVG: {}", path.display()))
+}
+
+/// Intrinsic size of the SVG in pixels, used like the dimensions of a raster image to fit it
+pub fn dimensions(path: &Path) -> Result<(u32, u32)> {
+    let size = load_tree(path)?.size();
+    Ok((size.width().ceil().max(1.0) as u32, size.height().ceil().max(1.0) as u32))
+}
+
+/// Rasterize the SVG to exactly `width` x `height` pixels. The drawing is stretched to that
+/// size, so callers keep the aspect ratio by fitting the size to `dimensions` first.
+pub fn rasterize(path: &Path, width: u32, height: u32) -> Result<RgbaImage> {
+    let tree = load_tree(path)?;
+    let (width, height) = (width.max(1), height.max(1));
+    let mut pixmap = tiny_skia::Pixmap::new(width, height).context("SVG raster size is too large")?;
+
+    let size = tree.size();
+    let transform = tiny_skia::Transform::from_scale(width as f32 / size.width(), height as f32 / size.height());
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    // tiny-skia works with premultiplied alpha, the block renderer and the protocols expect straight alpha
+    let mut image = RgbaImage::new(width, height);
+    for (pixel, color) in image.pixels_mut().zip(pixmap.pixels()) {
+        let color = color.demultiply();
+        *pixel = image::Rgba([color.red(), color.green(), color.blue(), color.alpha()]);
+    }
+
+    Ok(image)
+}
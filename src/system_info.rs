@@ -1,14 +1,252 @@
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::process::Command;
-use std::sync::OnceLock;
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use serde::ser::{SerializeMap, Serializer};
 use sysinfo::System;
 use rayon::prelude::*;
+use crate::cache::ModuleCache;
+use crate::config::TimeoutConfig;
+
+/// Memory usage in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryInfo {
+    pub used_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// A graphics adapter with its kernel driver, optional VRAM usage in bytes and temperature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GpuInfo {
+    pub name: String,
+    pub vendor: String,
+    pub driver: Option<String>,
+    pub driver_version: Option<String>,
+    pub vram_used_bytes: Option<u64>,
+    pub vram_total_bytes: Option<u64>,
+    pub temperature_celsius: Option<f32>,
+}
+
+/// A mounted filesystem with its size and usage in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DiskEntry {
+    pub device: String,
+    pub filesystem: String,
+    pub mount_point: String,
+    pub size_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+}
+
+impl DiskEntry {
+    /// Usage percentage calculated the same way as `df` (used / (used + available), rounded up)
+    pub fn usage_percent(&self) -> u32 {
+        let usable = self.used_bytes + self.available_bytes;
+        if usable == 0 {
+            return 0;
+        }
+        (self.used_bytes * 100).div_ceil(usable).min(100) as u32
+    }
+}
+
+/// Battery charge and charging status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatteryInfo {
+    pub percent: u8,
+    pub status: String,
+}
+
+/// Number of packages installed through a single package manager
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackageCount {
+    pub manager: String,
+    pub count: usize,
+}
+
+/// CPU and GPU temperatures in degrees Celsius
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemperatureSummary {
+    pub cpu_celsius: Option<f32>,
+    pub gpu_celsius: Option<f32>,
+}
+
+/// A hwmon temperature reading. `role` is what the chip measures: cpu, gpu, nvme, disk,
+/// chipset, board, ambient, wifi or other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemperatureSensor {
+    pub device: String,
+    pub chip: String,
+    pub label: String,
+    pub role: String,
+    pub celsius: f32,
+}
+
+/// An active display output and its current mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayOutput {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub refresh_hz: Option<f32>,
+    pub scale: Option<f32>,
+}
+
+/// A GPU as reported by nvidia-smi
+struct NvidiaSmiGpu {
+    bus_id: String,
+    name: String,
+    vram_used_bytes: Option<u64>,
+    vram_total_bytes: Option<u64>,
+    temperature_celsius: Option<f32>,
+    driver_version: Option<String>,
+}
+
+/// Value gathered for a single module. Formatting for the terminal happens in `display.rs`.
+/// Serialized untagged for the JSON output; the module cache stores it with an explicit tag.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum ModuleData {
+    Text(String),
+    Memory(MemoryInfo),
+    Gpus(Vec<GpuInfo>),
+    Disks(Vec<DiskEntry>),
+    Battery(BatteryInfo),
+    Packages(Vec<PackageCount>),
+    Temperature(Option<f32>),
+    Temperatures(TemperatureSummary),
+    Sensors(Vec<TemperatureSensor>),
+    Displays(Vec<DisplayOutput>),
+}
+
+/// Collector for a module that needs no shared state
+type ModuleCollector<'a> = Box<dyn Fn() -> ModuleData + Send + Sync + 'a>;
+
+/// Collector for a module that reads from the shared sysinfo state
+type SystemCollector = Box<dyn Fn(&System) -> ModuleData + Send + Sync>;
+
+/// Collector for a module that can include program versions (`show_versions`)
+type VersionCollector = Box<dyn Fn(bool) -> ModuleData + Send + Sync>;
+
+/// Installed package names per package manager
+type InstalledPackages = Vec<(&'static str, Vec<String>)>;
 
 #[derive(Debug, Clone)]
 pub struct SystemInfo {
-    pub data: HashMap<String, String>,
+    pub data: HashMap<String, ModuleData>,
+}
+
+impl Serialize for SystemInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Sort keys so machine-readable output is stable between runs
+        let mut keys: Vec<&String> = self.data.keys().collect();
+        keys.sort();
+
+        let mut map = serializer.serialize_map(Some(keys.len()))?;
+        for key in keys {
+            map.serialize_entry(&key.to_lowercase(), &self.data[key])?;
+        }
+        map.end()
+    }
+}
+
+thread_local! {
+    /// Deadline for external commands run by the module currently being collected on this thread
+    static COMMAND_DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    /// Set when a command was killed (or never started) because the deadline had passed
+    static COMMAND_TIMED_OUT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Per-module time limits, an overall deadline and the persistent cache for one collection run
+struct ModuleRunner<'a> {
+    timeouts: &'a TimeoutConfig,
+    deadline: Instant,
+    cache: ModuleCache,
+}
+
+impl<'a> ModuleRunner<'a> {
+    fn new(config: &'a crate::config::Config) -> Self {
+        let timeouts = &config.modules.timeouts;
+        Self {
+            timeouts,
+            deadline: Instant::now() + Duration::from_millis(timeouts.total_ms),
+            cache: ModuleCache::load(&config.cache),
+        }
+    }
+
+    /// Collect a module within its configured time limit
+    fn run(&self, key: &str, collect: impl FnOnce() -> Option<ModuleData>) -> Option<(String, ModuleData)> {
+        self.run_with_timeout(key, self.timeouts.module_ms, collect)
+    }
+
+    /// Collect a module with `default_ms` as its limit unless overridden in `timeouts.modules`.
+    /// Commands still running when the limit (or the overall deadline) is hit are killed, and
+    /// the module is reported as timed out or dropped, depending on `show_timed_out`.
+    /// A valid cached value is returned without collecting at all.
+    fn run_with_timeout(
+        &self,
+        key: &str,
+        default_ms: u64,
+        collect: impl FnOnce() -> Option<ModuleData>,
+    ) -> Option<(String, ModuleData)> {
+        if let Some(value) = self.cache.get(key) {
+            return Some((key.to_string(), value));
+        }
+        
+        let timeout_ms = self.timeouts.modules
+            .get(&key.to_lowercase())
+            .copied()
+            .unwrap_or(default_ms);
+        let deadline = self.deadline.min(Instant::now() + Duration::from_millis(timeout_ms));
+        
+        // In-process collectors always run; only their external commands are cut off
+        COMMAND_DEADLINE.with(|cell| cell.set(Some(deadline)));
+        COMMAND_TIMED_OUT.with(|cell| cell.set(false));
+        let result = collect();
+        let timed_out = COMMAND_TIMED_OUT.with(Cell::get);
+        COMMAND_DEADLINE.with(|cell| cell.set(None));
+        
+        if timed_out {
+            return self.timeouts.show_timed_out
+                .then(|| (key.to_string(), ModuleData::Text("timed out".to_string())));
+        }
+        let value = result?;
+        self.cache.store(key, &value);
+        Some((key.to_string(), value))
+    }
+}
+
+/// Share the result of `init` between the modules that need it, computing it once per run.
+/// A result that one of its commands timed out for is incomplete: it only goes to this caller,
+/// whose module is reported as timed out, and the next caller retries under its own deadline.
+fn memoized<T: Clone>(cell: &Mutex<Option<T>>, init: impl FnOnce() -> T) -> T {
+    let mut slot = cell.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(value) = slot.as_ref() {
+        return value.clone();
+    }
+    
+    // Only commands run by `init` decide whether its result can be shared
+    let timed_out_before = COMMAND_TIMED_OUT.with(|timed_out| timed_out.replace(false));
+    let value = init();
+    let timed_out = COMMAND_TIMED_OUT.with(|timed_out| timed_out.replace(timed_out_before || timed_out.get()));
+    
+    if !timed_out {
+        *slot = Some(value.clone());
+    }
+    value
 }
 
 impl SystemInfo {
@@ -19,128 +257,88 @@ impl SystemInfo {
         // Pre-cache commonly used files
         Self::cache_system_files();
         
+        let runner = ModuleRunner::new(config);
+        
         // Define all possible module collectors with their conditions
-        let module_collectors: Vec<(&str, Box<dyn Fn() -> String + Send + Sync>)> = vec![
+        let module_collectors: Vec<(&str, ModuleCollector)> = vec![
             // OS Information - Fast, no external commands
-            ("OS", Box::new(|| Self::get_os_info())),
-            ("KERNEL", Box::new(|| Self::get_kernel_version())),
-            ("LINUX", Box::new(|| Self::get_linux_info())),
-            ("UPTIME", Box::new(|| Self::format_uptime(System::uptime()))),
-            ("OS_AGE", Box::new(|| Self::get_os_age())),
+            ("OS", Box::new(|| ModuleData::Text(Self::get_os_info()))),
+            ("KERNEL", Box::new(|| ModuleData::Text(Self::get_kernel_version()))),
+            ("LINUX", Box::new(|| ModuleData::Text(Self::get_linux_info()))),
+            ("UPTIME", Box::new(|| ModuleData::Text(Self::format_uptime(System::uptime())))),
+            ("OS_AGE", Box::new(|| ModuleData::Text(Self::get_os_age()))),
             
             // Environment - Medium speed
-            ("DE", Box::new(|| Self::get_desktop_environment())),
-            ("WM", Box::new(|| Self::get_window_manager())),
-            ("USER", Box::new(|| Self::get_user_info())),
-            ("HOSTNAME", Box::new(|| Self::get_hostname_info())),
-            ("USER_AT_HOST", Box::new(|| Self::get_user_at_host_info())),
-            ("LOCALE", Box::new(|| Self::get_locale())),
-            ("THEME", Box::new(|| Self::get_theme())),
-            ("ICONS", Box::new(|| Self::get_icons())),
+            ("DE", Box::new(|| ModuleData::Text(Self::get_desktop_environment()))),
+            ("WM", Box::new(|| ModuleData::Text(Self::get_window_manager(config.modules.show_versions)))),
+            ("USER", Box::new(|| ModuleData::Text(Self::get_user_info()))),
+            ("HOSTNAME", Box::new(|| ModuleData::Text(Self::get_hostname_info()))),
+            ("USER_AT_HOST", Box::new(|| ModuleData::Text(Self::get_user_at_host_info()))),
+            ("LOCALE", Box::new(|| ModuleData::Text(Self::get_locale()))),
+            ("THEME", Box::new(|| ModuleData::Text(Self::get_theme()))),
+            ("ICONS", Box::new(|| ModuleData::Text(Self::get_icons()))),
         ];
         
         // System-dependent collectors (need sys reference)
-        let sys_collectors: Vec<(&str, Box<dyn Fn(&System) -> String + Send + Sync>)> = vec![
-            ("CPU", Box::new(|sys| Self::get_cpu_info(sys))),
-            ("MEMORY", Box::new(|sys| Self::get_memory_info(sys))),
+        let sys_collectors: Vec<(&str, SystemCollector)> = vec![
+            ("CPU", Box::new(|sys| ModuleData::Text(Self::get_cpu_info(sys)))),
+            ("MEMORY", Box::new(|sys| ModuleData::Memory(Self::get_memory_info(sys)))),
             ("DISK", Box::new(|sys| Self::get_disk_info(sys))),
         ];
         
         // Slow collectors (external commands) - these benefit most from parallelization
-        let slow_collectors: Vec<(&str, Box<dyn Fn() -> String + Send + Sync>)> = vec![
+        let slow_collectors: Vec<(&str, ModuleCollector)> = vec![
             ("GPU", Box::new(|| Self::get_gpu_info())),
-            ("GPU_DRIVER", Box::new(|| Self::get_gpu_driver_info())),
-            ("RESOLUTION", Box::new(|| Self::get_resolution())),
-            ("NETWORK", Box::new(|| Self::get_network_info())),
-            ("PUBLIC_IP", Box::new(|| Self::get_public_ip_info())),
+            ("GPU_DRIVER", Box::new(|| ModuleData::Text(Self::get_gpu_driver_info()))),
+            ("RESOLUTION", Box::new(Self::get_resolution)),
+            ("NETWORK", Box::new(|| ModuleData::Text(Self::get_network_info()))),
+            ("PUBLIC_IP", Box::new(|| ModuleData::Text(Self::get_public_ip_info()))),
             ("DYSK", Box::new(|| Self::get_dysk_info())),
-            ("CPU_TEMP", Box::new(|| Self::get_cpu_temperature())),
-            ("GPU_TEMP", Box::new(|| Self::get_gpu_temperature())),
-            ("TEMP_COMBINED", Box::new(|| Self::get_temp_combined())),
-            ("FONT", Box::new(|| Self::get_font_info())),
+            ("CPU_TEMP", Box::new(|| ModuleData::Temperature(Self::get_cpu_temperature()))),
+            ("GPU_TEMP", Box::new(|| ModuleData::Temperature(Self::get_gpu_temperature()))),
+            ("TEMP_COMBINED", Box::new(|| ModuleData::Temperatures(Self::get_temp_combined()))),
+            ("SENSORS", Box::new(|| ModuleData::Sensors(Self::get_selected_sensors(&config.modules.temperature_sensors)))),
+            ("FONT", Box::new(|| ModuleData::Text(Self::get_font_info()))),
         ];
         
         // Version-dependent collectors
-        let version_collectors: Vec<(&str, Box<dyn Fn(bool) -> String + Send + Sync>)> = vec![
+        let version_collectors: Vec<(&str, VersionCollector)> = vec![
             ("SHELL", Box::new(|show_versions| {
-                if show_versions { Self::get_shell_with_version() } else { Self::get_shell() }
+                ModuleData::Text(if show_versions { Self::get_shell_with_version() } else { Self::get_shell() })
             })),
             ("TERMINAL", Box::new(|show_versions| {
-                if show_versions { Self::get_terminal_with_version() } else { Self::get_terminal() }
+                ModuleData::Text(if show_versions { Self::get_terminal_with_version() } else { Self::get_terminal() })
             })),
             ("TERMINAL_SHELL_COMBINED", Box::new(|show_versions| {
-                Self::get_terminal_shell_combined(show_versions)
+                ModuleData::Text(Self::get_terminal_shell_combined(show_versions))
             })),
         ];
         
         // Parallel collection of fast modules (only enabled ones)
-        let fast_results: Vec<(String, String)> = module_collectors
+        let fast_results: Vec<(String, ModuleData)> = module_collectors
             .into_par_iter()
             .filter_map(|(key, collector)| {
-                let should_collect = match key {
-                    "OS" => config.modules.os,
-                    "KERNEL" => config.modules.kernel,
-                    "LINUX" => config.modules.linux,
-                    "UPTIME" => config.modules.uptime,
-                    "OS_AGE" => config.modules.os_age,
-                    "DE" => config.modules.de,
-                    "WM" => config.modules.wm,
-                    "USER" => config.modules.user,
-                    "HOSTNAME" => config.modules.hostname,
-                    "USER_AT_HOST" => config.modules.user_at_host,
-                    "LOCALE" => config.modules.locale,
-                    "THEME" => config.modules.theme,
-                    "ICONS" => config.modules.icons,
-                    _ => false,
-                };
-                
-                if should_collect {
-                    Some((key.to_string(), collector()))
+                if config.modules.should_collect(key) {
+                    runner.run(key, || Some(collector()))
                 } else {
                     None
                 }
             })
             .collect();
         
-        // Parallel collection of slow modules (only enabled ones)
-        let slow_results: Vec<(String, String)> = slow_collectors
-            .into_par_iter()
-            .filter_map(|(key, collector)| {
-                let should_collect = match key {
-                    "GPU" => config.modules.gpu,
-                    "GPU_DRIVER" => config.modules.gpu_driver,
-                    "RESOLUTION" => config.modules.resolution,
-                    "NETWORK" => config.modules.network,
-                    "PUBLIC_IP" => config.modules.public_ip,
-                    "DYSK" => config.modules.dysk,
-                    "CPU_TEMP" => config.modules.cpu_temp,
-                    "GPU_TEMP" => config.modules.gpu_temp,
-                    "TEMP_COMBINED" => config.modules.temp_combined,
-                    "FONT" => config.modules.font,
-                    _ => false,
-                };
-                
-                if should_collect {
-                    Some((key.to_string(), collector()))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        // Parallel collection of slow modules (only enabled ones), with user-defined
+        // custom command modules running alongside them
+        let (slow_results, custom_results) = rayon::join(
+            || Self::collect_slow_modules(slow_collectors, config, &runner),
+            || Self::collect_custom_modules(config, &runner),
+        );
         
         // Sequential collection of system-dependent modules
-        let sys_results: Vec<(String, String)> = sys_collectors
+        let sys_results: Vec<(String, ModuleData)> = sys_collectors
             .into_iter()
             .filter_map(|(key, collector)| {
-                let should_collect = match key {
-                    "CPU" => config.modules.cpu,
-                    "MEMORY" => config.modules.memory,
-                    "DISK" => config.modules.disk,
-                    _ => false,
-                };
-                
-                if should_collect {
-                    Some((key.to_string(), collector(&sys)))
+                if config.modules.should_collect(key) {
+                    runner.run(key, || Some(collector(&sys)))
                 } else {
                     None
                 }
@@ -148,18 +346,11 @@ impl SystemInfo {
             .collect();
         
         // Version-dependent modules
-        let version_results: Vec<(String, String)> = version_collectors
+        let version_results: Vec<(String, ModuleData)> = version_collectors
             .into_iter()
             .filter_map(|(key, collector)| {
-                let should_collect = match key {
-                    "SHELL" => config.modules.shell,
-                    "TERMINAL" => config.modules.terminal,
-                    "TERMINAL_SHELL_COMBINED" => config.modules.terminal_shell_combined,
-                    _ => false,
-                };
-                
-                if should_collect {
-                    Some((key.to_string(), collector(config.modules.show_versions)))
+                if config.modules.should_collect(key) {
+                    runner.run(key, || Some(collector(config.modules.show_versions)))
                 } else {
                     None
                 }
@@ -169,28 +360,28 @@ impl SystemInfo {
         // Optional modules (only if available and enabled)
         let mut optional_results = Vec::new();
         
-        if config.modules.battery {
-            if let Some(battery) = Self::get_battery_info() {
-                optional_results.push(("BATTERY".to_string(), battery));
-            }
+        if config.modules.should_collect("battery") {
+            optional_results.extend(runner.run("BATTERY", || {
+                Self::get_battery_info().map(ModuleData::Battery)
+            }));
         }
         
-        if config.modules.packages {
-            if let Some(packages) = Self::get_package_count() {
-                optional_results.push(("PACKAGES".to_string(), packages));
-            }
+        if config.modules.should_collect("packages") {
+            optional_results.extend(runner.run("PACKAGES", || {
+                Self::get_package_count().map(ModuleData::Packages)
+            }));
         }
         
-        if config.modules.flatpak_packages {
-            if let Some(flatpak_packages) = Self::get_flatpak_packages() {
-                optional_results.push(("FLATPAK_PACKAGES".to_string(), flatpak_packages));
-            }
+        if config.modules.should_collect("flatpak_packages") {
+            optional_results.extend(runner.run("FLATPAK_PACKAGES", || {
+                Self::get_flatpak_packages().map(|packages| ModuleData::Packages(vec![packages]))
+            }));
         }
         
-        if config.modules.packages_combined {
-            if let Some(combined_packages) = Self::get_combined_packages() {
-                optional_results.push(("PACKAGES_COMBINED".to_string(), combined_packages));
-            }
+        if config.modules.should_collect("packages_combined") {
+            optional_results.extend(runner.run("PACKAGES_COMBINED", || {
+                Self::get_combined_packages().map(ModuleData::Packages)
+            }));
         }
         
         // Combine all results
@@ -198,23 +389,60 @@ impl SystemInfo {
         
         for (key, value) in fast_results.into_iter()
             .chain(slow_results.into_iter())
+            .chain(custom_results)
             .chain(sys_results.into_iter())
             .chain(version_results.into_iter())
             .chain(optional_results.into_iter()) {
             data.insert(key, value);
         }
         
+        // The cache is best-effort; failing to write it must not break the greeting
+        let _ = runner.cache.save();
+        
         Self { data }
     }
     
+    fn collect_slow_modules(
+        slow_collectors: Vec<(&str, ModuleCollector<'_>)>,
+        config: &crate::config::Config,
+        runner: &ModuleRunner,
+    ) -> Vec<(String, ModuleData)> {
+        slow_collectors
+            .into_par_iter()
+            .filter_map(|(key, collector)| {
+                if config.modules.should_collect(key) {
+                    runner.run(key, || Some(collector()))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+    
+    fn collect_custom_modules(
+        config: &crate::config::Config,
+        runner: &ModuleRunner,
+    ) -> Vec<(String, ModuleData)> {
+        config.modules.custom
+            .par_iter()
+            .filter(|module| config.modules.should_collect(&module.key()))
+            .filter_map(|module| {
+                runner.run_with_timeout(&module.key().to_uppercase(), module.timeout_ms, || {
+                    let output = Self::run_command("sh", &["-c", &module.command])?;
+                    Some(ModuleData::Text(output.trim_end().to_string()))
+                })
+            })
+            .collect()
+    }
+    
     fn create_optimized_system(config: &crate::config::Config) -> System {
         let mut sys = System::new();
         
         // Only refresh components we actually need
-        if config.modules.cpu {
+        if config.modules.should_collect("cpu") {
             sys.refresh_cpu();
         }
-        if config.modules.memory {
+        if config.modules.should_collect("memory") {
             sys.refresh_memory();
         }
         // Note: disk info is gathered via external commands, not sysinfo
@@ -261,8 +489,28 @@ impl SystemInfo {
         "Unknown".to_string()
     }
 
+    /// `ID` followed by the `ID_LIKE` entries of /etc/os-release, most specific first
+    pub fn os_release_ids() -> Vec<String> {
+        let Some(content) = Self::get_cached_file("/etc/os-release") else {
+            return Vec::new();
+        };
+
+        let value = |key: &str| {
+            content.lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+                .map(|value| value.trim().trim_matches('"').trim_matches('\'').to_lowercase())
+        };
+
+        let mut ids: Vec<String> = value("ID").into_iter().collect();
+        if let Some(like) = value("ID_LIKE") {
+            ids.extend(like.split_whitespace().map(str::to_string));
+        }
+        ids
+    }
+
     fn get_kernel_version() -> String {
         Self::run_command("uname", &["-r"])
+            .map(|version| version.trim().to_string())
             .unwrap_or_else(|| "Unknown".to_string())
     }
 
@@ -306,9 +554,18 @@ impl SystemInfo {
         "Unknown".to_string()
     }
 
-    fn get_window_manager() -> String {
+    fn get_window_manager(show_versions: bool) -> String {
+        // Compositors that export an IPC socket can be identified (and asked for their version) directly
+        if let Some(compositor) = crate::compositor::detect() {
+            return match compositor.version {
+                Some(version) if show_versions => format!("{} {}", compositor.name, version),
+                _ => compositor.name,
+            };
+        }
+        
         // Check XDG_SESSION_TYPE first
         let session_type = env::var("XDG_SESSION_TYPE").unwrap_or_default();
+        let running = Self::get_running_process_names();
         
         // For Wayland sessions
         if session_type == "wayland" {
@@ -319,12 +576,16 @@ impl SystemInfo {
                 ("weston", "Weston"),
                 ("sway", "Sway"),
                 ("river", "River"),
+                ("Hyprland", "Hyprland"),
                 ("hyprland", "Hyprland"),
+                ("niri", "Niri"),
                 ("wayfire", "Wayfire"),
+                ("labwc", "labwc"),
+                ("cosmic-comp", "COSMIC"),
             ];
             
             for (process, name) in &wayland_wms {
-                if Self::run_command("pgrep", &["-x", process]).is_some() {
+                if running.iter().any(|running| running == process) {
                     return name.to_string();
                 }
             }
@@ -369,7 +630,7 @@ impl SystemInfo {
             ];
             
             for (process, name) in &x11_wms {
-                if Self::run_command("pgrep", &["-x", process]).is_some() {
+                if running.iter().any(|running| running == process) {
                     return name.to_string();
                 }
             }
@@ -397,6 +658,20 @@ impl SystemInfo {
         "Unknown".to_string()
     }
 
+    /// Names (comm) of all running processes, read from /proc
+    fn get_running_process_names() -> Vec<String> {
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        
+        entries
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().bytes().all(|byte| byte.is_ascii_digit()))
+            .filter_map(|entry| fs::read_to_string(entry.path().join("comm")).ok())
+            .map(|comm| comm.trim_end().to_string())
+            .collect()
+    }
+
     fn get_shell() -> String {
         env::var("SHELL")
             .map(|shell| {
@@ -522,40 +797,33 @@ impl SystemInfo {
         // Try to trace back through parent processes to find terminal
         let mut pid = current_pid;
         for _ in 0..10 { // Limit depth to avoid infinite loops
-            if let Ok(output) = Command::new("ps")
-                .args(&["-p", &pid.to_string(), "-o", "ppid=,comm="])
-                .output()
-            {
-                if let Ok(ps_output) = String::from_utf8(output.stdout) {
-                    let line = ps_output.trim();
-                    if line.is_empty() {
-                        break;
-                    }
+            if let Some(ps_output) = Self::run_command("ps", &["-p", &pid.to_string(), "-o", "ppid=,comm="]) {
+                let line = ps_output.trim();
+                if line.is_empty() {
+                    break;
+                }
+                
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() >= 2 {
+                    let parent_pid = parts[0];
+                    let comm = parts[1];
                     
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() >= 2 {
-                        let parent_pid = parts[0];
-                        let comm = parts[1];
-                        
-                        // Check if this is a known terminal
-                        match comm {
-                            "alacritty" | "kitty" | "ghostty" | "wezterm" | 
-                            "gnome-terminal" | "konsole" | "xterm" | "urxvt" |
-                            "terminator" | "tilix" | "st" | "foot" => {
-                                return Some(comm.to_string());
-                            }
-                            _ => {}
+                    // Check if this is a known terminal
+                    match comm {
+                        "alacritty" | "kitty" | "ghostty" | "wezterm" | 
+                        "gnome-terminal" | "konsole" | "xterm" | "urxvt" |
+                        "terminator" | "tilix" | "st" | "foot" => {
+                            return Some(comm.to_string());
                         }
-                        
-                        // Continue with parent process
-                        if let Ok(ppid) = parent_pid.parse::<u32>() {
-                            if ppid == 1 || ppid == pid {
-                                break; // Reached init or circular reference
-                            }
-                            pid = ppid;
-                        } else {
-                            break;
+                        _ => {}
+                    }
+                    
+                    // Continue with parent process
+                    if let Ok(ppid) = parent_pid.parse::<u32>() {
+                        if ppid == 1 || ppid == pid {
+                            break; // Reached init or circular reference
                         }
+                        pid = ppid;
                     } else {
                         break;
                     }
@@ -679,50 +947,210 @@ impl SystemInfo {
         }
     }
 
-    fn get_gpu_info() -> String {
-        // Try nvidia-smi first for NVIDIA cards (gives cleaner names)
-        if let Some(output) = Self::run_command("nvidia-smi", &["--query-gpu=name", "--format=csv,noheader"]) {
-            if let Some(gpu) = output.lines().next() {
-                let clean_name = gpu.trim();
-                if !clean_name.is_empty() {
-                    // Try to get VRAM usage for NVIDIA GPUs (used/total)
-                    if let Some(vram_output) = Self::run_command("nvidia-smi", &["--query-gpu=memory.used,memory.total", "--format=csv,noheader,nounits"]) {
-                        if let Some(vram_line) = vram_output.lines().next() {
-                            let parts: Vec<&str> = vram_line.split(',').map(|s| s.trim()).collect();
-                            if parts.len() == 2 {
-                                if let (Ok(used_mb), Ok(total_mb)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-                                    let used_gb = used_mb as f64 / 1024.0;
-                                    let total_gb = total_mb as f64 / 1024.0;
-                                    return format!("{} ({:.1}GB / {:.1}GB)", clean_name, used_gb, total_gb);
-                                }
-                            }
-                        }
-                    }
-                    // Fallback to name only if VRAM query fails
-                    return clean_name.to_string();
-                }
+    fn get_gpu_info() -> ModuleData {
+        let gpus = Self::get_gpus();
+        if !gpus.is_empty() {
+            return ModuleData::Gpus(gpus);
+        }
+        
+        // Without sysfs (e.g. in containers), fall back to lspci listing
+        if let Some(output) = Self::run_command("lspci", &[]) {
+            let gpus: Vec<GpuInfo> = output.lines()
+                .filter(|line| line.contains("VGA compatible controller") || line.contains("3D controller"))
+                .filter_map(|line| line.split(": ").nth(1))
+                .map(|gpu| GpuInfo {
+                    name: Self::parse_gpu_name(gpu),
+                    vendor: Self::parse_gpu_vendor(gpu),
+                    driver: None,
+                    driver_version: None,
+                    vram_used_bytes: None,
+                    vram_total_bytes: None,
+                    temperature_celsius: None,
+                })
+                .collect();
+            if !gpus.is_empty() {
+                return ModuleData::Gpus(gpus);
             }
         }
 
-        // Try lspci as fallback (and attempt VRAM detection for AMD/Intel)
-        if let Some(output) = Self::run_command("lspci", &[]) {
-            for line in output.lines() {
-                if line.contains("VGA compatible controller") || line.contains("3D controller") {
-                    if let Some(gpu) = line.split(": ").nth(1) {
-                        let clean_gpu_name = Self::parse_gpu_name(gpu);
-                        
-                        // Try to get VRAM usage for AMD/Intel GPUs
-                        if let Some((used_gb, total_gb)) = Self::get_non_nvidia_vram_usage() {
-                            return format!("{} ({:.1}GB / {:.1}GB)", clean_gpu_name, used_gb, total_gb);
-                        }
-                        
-                        return clean_gpu_name;
+        ModuleData::Text("Unknown GPU".to_string())
+    }
+
+    /// Every display controller found under /sys/class/drm, with its bound driver,
+    /// VRAM and temperature. NVIDIA's proprietary driver exposes neither in sysfs,
+    /// so those come from a single nvidia-smi query.
+    fn get_gpus() -> Vec<GpuInfo> {
+        static GPUS: Mutex<Option<Vec<GpuInfo>>> = Mutex::new(None);
+        
+        memoized(&GPUS, || {
+            let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+                return Vec::new();
+            };
+            
+            // card0, card1, ... (connectors such as card0-DP-1 are skipped)
+            let mut cards: Vec<_> = entries.flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .and_then(|name| name.strip_prefix("card"))
+                        .is_some_and(|index| index.parse::<u32>().is_ok())
+                })
+                .collect();
+            cards.sort();
+            
+            let mut addresses = Vec::new();
+            for card in &cards {
+                let device = card.join("device");
+                // Only PCI devices have a vendor ID; this skips simpledrm and similar framebuffers
+                if !device.join("vendor").exists() {
+                    continue;
+                }
+                if let Ok(address) = fs::canonicalize(&device) {
+                    let address = address.file_name().unwrap_or_default().to_string_lossy().to_string();
+                    if !addresses.iter().any(|(existing, _)| existing == &address) {
+                        addresses.push((address, device));
                     }
                 }
             }
-        }
+            if addresses.is_empty() {
+                return Vec::new();
+            }
+            
+            let nvidia = Self::get_nvidia_smi_gpus();
+            // lspci is only needed for devices missing from pci.ids (or when it isn't installed)
+            let lspci = std::cell::OnceCell::new();
+            
+            addresses.into_iter()
+                .map(|(address, device)| {
+                    let read = |file: &str| fs::read_to_string(device.join(file)).ok().map(|value| value.trim().to_string());
+                    let vendor_id = read("vendor").unwrap_or_default();
+                    let driver = fs::read_link(device.join("driver")).ok()
+                        .and_then(|link| link.file_name().map(|name| name.to_string_lossy().to_string()));
+                    let driver_version = driver.as_ref()
+                        .and_then(|driver| fs::read_to_string(format!("/sys/module/{}/version", driver)).ok())
+                        .map(|version| version.trim().to_string());
+                    
+                    let vendor = match vendor_id.as_str() {
+                        "0x10de" => "NVIDIA",
+                        "0x1002" => "AMD",
+                        "0x8086" => "Intel",
+                        _ => "Unknown",
+                    }.to_string();
+                    
+                    let parse_id = |id: String| u16::from_str_radix(id.trim_start_matches("0x"), 16).ok();
+                    let name = parse_id(vendor_id.clone())
+                        .zip(read("device").and_then(parse_id))
+                        .and_then(|(vendor_id, device_id)| crate::pci_ids::device_name(vendor_id, device_id))
+                        .or_else(|| {
+                            lspci.get_or_init(Self::get_lspci_devices)
+                                .get(&address)
+                                .map(|raw| Self::parse_gpu_name(raw))
+                        })
+                        .unwrap_or_else(|| format!("{} GPU", vendor));
+                    
+                    let mut gpu = GpuInfo {
+                        name,
+                        vendor,
+                        driver,
+                        driver_version,
+                        vram_used_bytes: read("mem_info_vram_used").and_then(|value| value.parse().ok()),
+                        vram_total_bytes: read("mem_info_vram_total").and_then(|value| value.parse().ok()),
+                        temperature_celsius: Self::get_device_temperature(&device),
+                    };
+                    
+                    // nvidia-smi reports bus IDs as "00000000:01:00.0"; compare bus:device.function
+                    let bus_id = address.rsplit_once(':').map(|(rest, function)| {
+                        format!("{}:{}", rest.rsplit(':').next().unwrap_or_default(), function)
+                    });
+                    if let Some(smi) = nvidia.iter().find(|smi| Some(&smi.bus_id) == bus_id.as_ref()) {
+                        gpu.name = smi.name.clone();
+                        gpu.vram_used_bytes = smi.vram_used_bytes.or(gpu.vram_used_bytes);
+                        gpu.vram_total_bytes = smi.vram_total_bytes.or(gpu.vram_total_bytes);
+                        gpu.temperature_celsius = smi.temperature_celsius.or(gpu.temperature_celsius);
+                        gpu.driver_version = gpu.driver_version.take().or_else(|| smi.driver_version.clone());
+                    }
+                    
+                    gpu
+                })
+                .collect()
+        })
+    }
+
+    /// GPU edge (or first) temperature from the device's own hwmon directory
+    fn get_device_temperature(device: &std::path::Path) -> Option<f32> {
+        let hwmon = fs::read_dir(device.join("hwmon")).ok()?.flatten().next()?.path();
+        
+        let read_temp = |index: u32| {
+            fs::read_to_string(hwmon.join(format!("temp{}_input", index))).ok()?
+                .trim().parse::<f32>().ok()
+                .map(|millidegrees| millidegrees / 1000.0)
+        };
+        
+        (1..=4)
+            .find(|index| {
+                fs::read_to_string(hwmon.join(format!("temp{}_label", index)))
+                    .is_ok_and(|label| label.trim() == "edge")
+            })
+            .and_then(read_temp)
+            .or_else(|| read_temp(1))
+    }
+
+    /// Per-GPU data from nvidia-smi, keyed by "bus:device.function"
+    fn get_nvidia_smi_gpus() -> Vec<NvidiaSmiGpu> {
+        const MIB: u64 = 1024 * 1024;
+        
+        let Some(output) = Self::run_command("nvidia-smi", &[
+            "--query-gpu=pci.bus_id,name,memory.used,memory.total,temperature.gpu,driver_version",
+            "--format=csv,noheader,nounits",
+        ]) else {
+            return Vec::new();
+        };
+        
+        output.lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+                if fields.len() < 6 {
+                    return None;
+                }
+                Some(NvidiaSmiGpu {
+                    bus_id: fields[0].rsplit_once(':').map(|(rest, function)| {
+                        format!("{}:{}", rest.rsplit(':').next().unwrap_or_default(), function)
+                    })?.to_lowercase(),
+                    name: fields[1].to_string(),
+                    vram_used_bytes: fields[2].parse::<u64>().ok().map(|mib| mib * MIB),
+                    vram_total_bytes: fields[3].parse::<u64>().ok().map(|mib| mib * MIB),
+                    temperature_celsius: fields[4].parse().ok(),
+                    driver_version: Some(fields[5].to_string()).filter(|version| !version.is_empty()),
+                })
+            })
+            .collect()
+    }
+
+    /// Raw lspci descriptions keyed by full PCI address ("0000:01:00.0")
+    fn get_lspci_devices() -> HashMap<String, String> {
+        Self::run_command("lspci", &["-D"])
+            .map(|output| {
+                output.lines()
+                    .filter_map(|line| {
+                        let (address, rest) = line.split_once(' ')?;
+                        Some((address.to_string(), rest.split_once(": ")?.1.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 
-        "Unknown GPU".to_string()
+    fn parse_gpu_vendor(raw_name: &str) -> String {
+        if raw_name.contains("NVIDIA") {
+            "NVIDIA".to_string()
+        } else if raw_name.contains("Advanced Micro Devices") || raw_name.contains("AMD") || raw_name.contains("ATI") {
+            "AMD".to_string()
+        } else if raw_name.contains("Intel") {
+            "Intel".to_string()
+        } else {
+            raw_name.split_whitespace().next().unwrap_or("Unknown").to_string()
+        }
     }
 
     fn parse_gpu_name(raw_name: &str) -> String {
@@ -1126,9 +1554,44 @@ impl SystemInfo {
         format!("{}@{}", user, hostname)
     }
 
+    /// Human-readable name of a GPU kernel driver, e.g. "AMDGPU (open source)"
+    fn describe_gpu_driver(driver: &str, version: Option<&str>) -> String {
+        let name = match driver {
+            "nvidia" if Self::is_nvidia_open_source_driver() => "NVIDIA (open source)",
+            "nvidia" => "NVIDIA (proprietary)",
+            "amdgpu" => "AMDGPU (open source)",
+            "radeon" => "Radeon (open source)",
+            "i915" => "Intel i915 (open source)",
+            "xe" => "Intel Xe (open source)",
+            "nouveau" => "Nouveau (open source)",
+            "vmwgfx" => "VMware SVGA (open source)",
+            "virtio-pci" | "virtio_gpu" => "VirtIO GPU (open source)",
+            other => other,
+        };
+        
+        match version {
+            Some(version) => format!("{} {}", name, version),
+            None => name.to_string(),
+        }
+    }
+
     fn get_gpu_driver_info() -> String {
         // Try to detect GPU driver from various sources
         
+        // Method 0: Drivers bound to each GPU in sysfs
+        let mut drivers: Vec<String> = Vec::new();
+        for gpu in Self::get_gpus() {
+            if let Some(driver) = &gpu.driver {
+                let description = Self::describe_gpu_driver(driver, gpu.driver_version.as_deref());
+                if !drivers.contains(&description) {
+                    drivers.push(description);
+                }
+            }
+        }
+        if !drivers.is_empty() {
+            return drivers.join(", ");
+        }
+        
         // Method 1: Check loaded kernel modules for GPU drivers
         if let Some(output) = Self::run_command("lsmod", &[]) {
             let lines: Vec<&str> = output.lines().collect();
@@ -1268,83 +1731,47 @@ impl SystemInfo {
         "Unknown Driver".to_string()
     }
 
-    fn get_memory_info(sys: &System) -> String {
-        let total_mem = sys.total_memory() / 1024 / 1024; // Convert to MB
-        let used_mem = sys.used_memory() / 1024 / 1024;
-        let total_gb = total_mem as f64 / 1024.0;
-        let used_gb = used_mem as f64 / 1024.0;
-        
-        format!("{:.1}GB / {:.1}GB ({:.0}%)", 
-                used_gb, total_gb, (used_mem as f64 / total_mem as f64) * 100.0)
+    fn get_memory_info(sys: &System) -> MemoryInfo {
+        MemoryInfo {
+            used_bytes: sys.used_memory(),
+            total_bytes: sys.total_memory(),
+        }
     }
 
-    fn get_disk_info(_sys: &System) -> String {
+    fn get_disk_info(_sys: &System) -> ModuleData {
         // Get specific important mountpoints with filesystem info
         let mut disk_info = Vec::new();
         
         // Always check root partition first
-        if let Some(info) = Self::get_disk_info_with_filesystem("/") {
-            if !info.is_empty() && info != "Unknown" {
-                disk_info.push(format!("/ {}", info));
-            }
+        if let Some(entry) = Self::get_disk_entry("/") {
+            disk_info.push(entry);
         }
         
-        // Check /boot/efi if it exists
-        if let Some(info) = Self::get_disk_info_with_filesystem("/boot/efi") {
-            if !info.is_empty() && info != "Unknown" {
-                disk_info.push(format!("/boot {}", info));
-            }
+        // Check /boot/efi if it is mounted (df reports the parent filesystem otherwise)
+        if let Some(entry) = Self::get_disk_entry("/boot/efi")
+            && entry.mount_point == "/boot/efi"
+        {
+            disk_info.push(entry);
         }
         
         // Check /home only if it's on a separate device from /
         if Self::is_separate_partition("/", "/home") {
-            if let Some(info) = Self::get_disk_info_with_filesystem("/home") {
-                if !info.is_empty() && info != "Unknown" {
-                    disk_info.push(format!("/home {}", info));
-                }
+            if let Some(entry) = Self::get_disk_entry("/home") {
+                disk_info.push(entry);
             }
         }
         
-        // Join with bullet separator
-        if !disk_info.is_empty() {
-            disk_info.join(" • ")
+        if disk_info.is_empty() {
+            ModuleData::Text("Unknown".to_string())
         } else {
-            // Fallback: try to get at least root filesystem info
-            Self::get_disk_info_with_filesystem("/").unwrap_or_else(|| "Unknown".to_string())
+            ModuleData::Disks(disk_info)
         }
     }
     
-    fn get_single_disk_info(mount_point: &str) -> Option<String> {
-        // Simple wrapper for backward compatibility
-        Self::get_single_disk_info_with_device(mount_point).map(|(info, _)| info)
-    }
-    
-    fn get_disk_info_with_filesystem(mount_point: &str) -> Option<String> {
-        // Try multiple approaches to get disk info with filesystem type
-        
-        // Method 1: Use df -hT command with POSIX locale
-        if let Some(output) = Self::run_command_with_env("df", &["-hT", mount_point], &[("LC_ALL", "C")]) {
-            if let Some(result) = Self::parse_df_output_with_fs(&output, "LC_ALL=C") {
-                return Some(result);
-            }
-        }
-        
-        // Method 2: Try df -hT without locale override
-        if let Some(output) = Self::run_command("df", &["-hT", mount_point]) {
-            if let Some(result) = Self::parse_df_output_with_fs(&output, "default") {
-                return Some(result);
-            }
-        }
-        
-        // Method 3: Try df -hT with explicit LANG=C
-        if let Some(output) = Self::run_command_with_env("df", &["-hT", mount_point], &[("LANG", "C")]) {
-            if let Some(result) = Self::parse_df_output_with_fs(&output, "LANG=C") {
-                return Some(result);
-            }
-        }
-        
-        // Fallback: try without filesystem type
-        Self::get_single_disk_info(mount_point)
+    fn get_disk_entry(mount_point: &str) -> Option<DiskEntry> {
+        // Use df with byte-sized blocks and POSIX locale so the output is parseable
+        let output = Self::run_command_with_env("df", &["-T", "-B1", mount_point], &[("LC_ALL", "C")])?;
+        Self::parse_df_bytes(&output).into_iter().next()
     }
     
     fn is_separate_partition(mount1: &str, mount2: &str) -> bool {
@@ -1362,241 +1789,253 @@ impl SystemInfo {
         // Get the device name for a specific mount point using df
         if let Some(output) = Self::run_command_with_env("df", &[mount_point], &[("LC_ALL", "C")]) {
             for line in output.lines().skip(1) { // Skip header line
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 1 {
-                    return Some(parts[0].to_string());
+                if let Some(device) = line.split_whitespace().next() {
+                    return Some(device.to_string());
                 }
             }
         }
         None
     }
     
-    fn get_single_disk_info_with_device(mount_point: &str) -> Option<(String, String)> {
-        // Try multiple approaches to get disk info for a specific mount point
+    fn parse_df_bytes(output: &str) -> Vec<DiskEntry> {
+        let mut entries = Vec::new();
         
-        // Method 1: Use df command with POSIX locale
-        if let Some(output) = Self::run_command_with_env("df", &["-h", mount_point], &[("LC_ALL", "C")]) {
-            if let Some((result, device)) = Self::parse_df_output_with_device(&output, "LC_ALL=C") {
-                return Some((result, device));
+        for line in output.lines().skip(1) { // Skip header line
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            
+            // df -T -B1 output format: Filesystem Type 1B-blocks Used Available Use% Mounted
+            if parts.len() >= 7 {
+                if let (Ok(size_bytes), Ok(used_bytes), Ok(available_bytes)) = (
+                    parts[2].parse::<u64>(),
+                    parts[3].parse::<u64>(),
+                    parts[4].parse::<u64>(),
+                ) {
+                    entries.push(DiskEntry {
+                        device: parts[0].to_string(),
+                        filesystem: parts[1].to_string(),
+                        // Mount points may contain spaces
+                        mount_point: parts[6..].join(" "),
+                        size_bytes,
+                        used_bytes,
+                        available_bytes,
+                    });
+                }
             }
         }
         
-        // Method 2: Try df without locale override
-        if let Some(output) = Self::run_command("df", &["-h", mount_point]) {
-            if let Some((result, device)) = Self::parse_df_output_with_device(&output, "default") {
-                return Some((result, device));
-            }
-        }
+        entries
+    }
+
+    /// Every active output with its current mode, asking the compositor first since
+    /// xrandr only sees XWayland on Wayland sessions
+    fn get_resolution() -> ModuleData {
+        let sources: [fn() -> Option<Vec<DisplayOutput>>; 6] = [
+            Self::get_hyprland_outputs,
+            Self::get_sway_outputs,
+            Self::get_niri_outputs,
+            Self::get_wlr_randr_outputs,
+            Self::get_xrandr_outputs,
+            Self::get_drm_outputs,
+        ];
         
-        // Method 3: Try df with explicit LANG=C
-        if let Some(output) = Self::run_command_with_env("df", &["-h", mount_point], &[("LANG", "C")]) {
-            if let Some((result, device)) = Self::parse_df_output_with_device(&output, "LANG=C") {
-                return Some((result, device));
+        for source in sources {
+            if let Some(outputs) = source()
+                && !outputs.is_empty()
+            {
+                return ModuleData::Displays(outputs);
             }
         }
         
-        None
+        ModuleData::Text("Unknown".to_string())
     }
-    
 
-    
-    fn parse_df_output_with_device(output: &str, _method: &str) -> Option<(String, String)> {
-        for line in output.lines().skip(1) { // Skip header line
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            
-            // Handle different df output formats
-            if parts.len() >= 6 {
-                // Standard format: Filesystem Size Used Avail Use% Mounted
-                let device = parts[0].to_string();
-                let total = parts[1];
-                let used = parts[2];
-                let usage = parts[4];
-                return Some((format!("{} / {} ({})", used, total, usage), device));
-            } else if parts.len() >= 5 {
-                // Alternative format: might have filesystem on separate line
-                let device = parts[0].to_string();
-                let total = parts[1];
-                let used = parts[2];
-                let usage = parts[4];
-                return Some((format!("{} / {} ({})", used, total, usage), device));
-            } else if parts.len() == 4 {
-                // Another format variation
-                let device = "unknown".to_string();
-                let total = parts[0];
-                let used = parts[1];
-                let usage = parts[2];
-                return Some((format!("{} / {} ({})", used, total, usage), device));
-            }
-        }
-        None
+    fn get_hyprland_outputs() -> Option<Vec<DisplayOutput>> {
+        let reply = crate::compositor::hyprland_request("j/monitors")?;
+        let monitors: Vec<serde_json::Value> = serde_json::from_str(&reply).ok()?;
+        
+        Some(monitors
+            .iter()
+            .filter(|monitor| !monitor["disabled"].as_bool().unwrap_or(false))
+            .filter_map(|monitor| Some(DisplayOutput {
+                name: monitor["name"].as_str()?.to_string(),
+                width: monitor["width"].as_u64()? as u32,
+                height: monitor["height"].as_u64()? as u32,
+                refresh_hz: monitor["refreshRate"].as_f64().map(|rate| rate as f32),
+                scale: monitor["scale"].as_f64().map(|scale| scale as f32),
+            }))
+            .collect())
     }
-    
-    fn parse_df_output_with_fs(output: &str, _method: &str) -> Option<String> {
-        for line in output.lines().skip(1) { // Skip header line
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            
-            // Handle df -hT output format: Filesystem Type Size Used Avail Use% Mounted
-            if parts.len() >= 7 {
-                // Standard format with filesystem type: Filesystem Type Size Used Avail Use% Mounted
-                let filesystem = parts[1];
-                let total = parts[2];
-                let used = parts[3];
-                let usage = parts[5];
-                return Some(format!("{} / {} ({}) [{}]", used, total, usage, filesystem));
-            } else if parts.len() >= 6 {
-                // Alternative format: might be missing some fields
-                let filesystem = parts[1];
-                let total = parts[2];
-                let used = parts[3];
-                let usage = parts[4];
-                return Some(format!("{} / {} ({}) [{}]", used, total, usage, filesystem));
-            }
-        }
-        None
+
+    fn get_sway_outputs() -> Option<Vec<DisplayOutput>> {
+        let reply = crate::compositor::i3_request(crate::compositor::I3_GET_OUTPUTS, "")?;
+        
+        Some(reply
+            .as_array()?
+            .iter()
+            .filter(|output| output["active"].as_bool().unwrap_or(false))
+            .filter_map(|output| {
+                let mode = &output["current_mode"];
+                Some(DisplayOutput {
+                    name: output["name"].as_str()?.to_string(),
+                    width: mode["width"].as_u64()? as u32,
+                    height: mode["height"].as_u64()? as u32,
+                    // Sway reports the refresh rate in mHz
+                    refresh_hz: mode["refresh"].as_f64().map(|refresh| refresh as f32 / 1000.0),
+                    scale: output["scale"].as_f64().map(|scale| scale as f32),
+                })
+            })
+            .collect())
     }
 
-    fn get_resolution() -> String {
-        // Try xrandr for X11
-        if let Some(output) = Self::run_command("xrandr", &["--current"]) {
-            let lines: Vec<&str> = output.lines().collect();
-            let mut current_display_resolution = None;
-            
-            // First pass: find the connected display and its resolution
-            for (i, line) in lines.iter().enumerate() {
-                if line.contains(" connected primary") || (line.contains(" connected ") && !line.contains(" disconnected")) {
-                    // Extract resolution from connected line like "DP-3 connected primary 3440x1440+0+0"
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    for part in &parts {
-                        if part.contains("x") && part.chars().next().unwrap_or('a').is_ascii_digit() {
-                            let resolution = part.split('+').next().unwrap_or("Unknown");
-                            current_display_resolution = Some((resolution.to_string(), i));
-                            break;
-                        }
-                    }
-                    break;
-                }
+    fn get_niri_outputs() -> Option<Vec<DisplayOutput>> {
+        let reply = crate::compositor::niri_request("\"Outputs\"")?;
+        
+        let mut outputs: Vec<DisplayOutput> = reply["Ok"]["Outputs"]
+            .as_object()?
+            .values()
+            .filter_map(|output| {
+                // Outputs that are turned off have no current mode
+                let mode = &output["modes"][output["current_mode"].as_u64()? as usize];
+                Some(DisplayOutput {
+                    name: output["name"].as_str()?.to_string(),
+                    width: mode["width"].as_u64()? as u32,
+                    height: mode["height"].as_u64()? as u32,
+                    refresh_hz: mode["refresh_rate"].as_f64().map(|refresh| refresh as f32 / 1000.0),
+                    scale: output["logical"]["scale"].as_f64().map(|scale| scale as f32),
+                })
+            })
+            .collect();
+        outputs.sort_by(|a, b| a.name.cmp(&b.name));
+        Some(outputs)
+    }
+
+    /// Parse `wlr-randr` output: an unindented header per output followed by indented
+    /// properties, with the active mode marked "current"
+    fn get_wlr_randr_outputs() -> Option<Vec<DisplayOutput>> {
+        let output = Self::run_command("wlr-randr", &[])?;
+        let mut outputs = Vec::new();
+        let mut name = None;
+        let mut enabled = true;
+        let mut mode = None;
+        let mut scale = None;
+        
+        let mut finish = |name: Option<String>, enabled: bool, mode: Option<(u32, u32, Option<f32>)>, scale| {
+            if let (Some(name), true, Some((width, height, refresh_hz))) = (name, enabled, mode) {
+                outputs.push(DisplayOutput { name, width, height, refresh_hz, scale });
             }
-            
-            // Second pass: look for the refresh rate in the following indented lines
-            if let Some((resolution, display_line_idx)) = current_display_resolution {
-                // Look at the lines following the connected display line
-                for line in lines.iter().skip(display_line_idx + 1) {
-                    // Stop if we hit another display or non-indented line
-                    if !line.starts_with("   ") || line.contains(" connected") {
-                        break;
-                    }
-                    
-                    // Look for the line with the current resolution and * marker
-                    if line.contains("*") {
-                        let parts: Vec<&str> = line.trim().split_whitespace().collect();
-                        if let Some(line_resolution) = parts.first() {
-                            // Check if this line matches our display resolution
-                            if line_resolution == &resolution {
-                                // Look for refresh rate in the same line
-                                for part in &parts {
-                                    if part.contains("*") {
-                                        let rate_str = part.replace("*", "").replace("+", "");
-                                        if let Ok(rate) = rate_str.parse::<f32>() {
-                                            return format!("{} @ {:.0}Hz", resolution, rate);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+        };
+        
+        for line in output.lines() {
+            if !line.starts_with(' ') && !line.trim().is_empty() {
+                finish(name.take(), enabled, mode.take(), scale.take());
+                name = line.split_whitespace().next().map(str::to_string);
+                enabled = true;
+            } else if let Some(value) = line.trim().strip_prefix("Enabled:") {
+                enabled = value.trim() == "yes";
+            } else if let Some(value) = line.trim().strip_prefix("Scale:") {
+                scale = value.trim().parse::<f32>().ok();
+            } else if line.contains("current") {
+                // "    2560x1440 px, 143.912003 Hz (preferred, current)"
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let resolution = parts.first().and_then(|part| part.split_once('x'));
+                if let Some((width, height)) = resolution
+                    && let (Ok(width), Ok(height)) = (width.parse(), height.parse())
+                {
+                    let refresh_hz = parts.iter()
+                        .position(|part| part.starts_with("Hz"))
+                        .and_then(|index| parts.get(index.checked_sub(1)?))
+                        .and_then(|rate| rate.parse::<f32>().ok());
+                    mode = Some((width, height, refresh_hz));
                 }
-                
-                // If no refresh rate found, return just resolution
-                return resolution;
             }
         }
+        finish(name, enabled, mode, scale);
+        
+        Some(outputs)
+    }
 
-        // Try wlr-randr for Wayland
-        if let Some(output) = Self::run_command("wlr-randr", &[]) {
-            for line in output.lines() {
-                if line.contains("current") {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    let mut resolution = None;
-                    let mut refresh_rate = None;
-                    
-                    for part in &parts {
-                        if part.contains("x") && part.chars().next().unwrap_or('a').is_ascii_digit() {
-                            resolution = Some(part.to_string());
-                        } else if part.ends_with("Hz") {
-                            if let Ok(rate) = part.replace("Hz", "").parse::<f32>() {
-                                refresh_rate = Some(rate);
-                            }
-                        }
-                    }
-                    
-                    if let Some(res) = resolution {
-                        if let Some(rate) = refresh_rate {
-                            return format!("{} @ {:.0}Hz", res, rate);
-                        } else {
-                            return res;
-                        }
-                    }
-                }
-            }
-        }
+    /// Parse `xrandr --current`: every connected output that has a geometry (i.e. is active),
+    /// with the refresh rate taken from its mode line marked "*"
+    fn get_xrandr_outputs() -> Option<Vec<DisplayOutput>> {
+        let output = Self::run_command("xrandr", &["--current"])?;
+        let mut outputs: Vec<DisplayOutput> = Vec::new();
+        let mut in_active_output = false;
         
-        // Try swaymsg for Sway/Wayland
-        if let Some(output) = Self::run_command("swaymsg", &["-t", "get_outputs"]) {
-            // This would require JSON parsing, but let's try a simple approach
-            if output.contains("current_mode") {
-                // Look for patterns like "width":3440,"height":1440,"refresh":165000
-                let lines: Vec<&str> = output.lines().collect();
-                for line in lines {
-                    if line.contains("current_mode") {
-                        // Simple regex-like parsing for width, height, refresh
-                        let mut width = None;
-                        let mut height = None;
-                        let mut refresh = None;
-                        
-                        if let Some(w_start) = line.find("\"width\":") {
-                            if let Some(w_end) = line[w_start+8..].find(',') {
-                                if let Ok(w) = line[w_start+8..w_start+8+w_end].parse::<u32>() {
-                                    width = Some(w);
-                                }
-                            }
-                        }
-                        
-                        if let Some(h_start) = line.find("\"height\":") {
-                            if let Some(h_end) = line[h_start+9..].find(',') {
-                                if let Ok(h) = line[h_start+9..h_start+9+h_end].parse::<u32>() {
-                                    height = Some(h);
-                                }
-                            }
-                        }
-                        
-                        if let Some(r_start) = line.find("\"refresh\":") {
-                            if let Some(r_end) = line[r_start+10..].find(',') {
-                                if let Ok(r) = line[r_start+10..r_start+10+r_end].parse::<u32>() {
-                                    refresh = Some(r / 1000); // Convert from mHz to Hz
-                                }
-                            } else if let Some(r_end) = line[r_start+10..].find('}') {
-                                if let Ok(r) = line[r_start+10..r_start+10+r_end].parse::<u32>() {
-                                    refresh = Some(r / 1000); // Convert from mHz to Hz
-                                }
-                            }
-                        }
-                        
-                        if let (Some(w), Some(h)) = (width, height) {
-                            if let Some(r) = refresh {
-                                return format!("{}x{} @ {}Hz", w, h, r);
-                            } else {
-                                return format!("{}x{}", w, h);
-                            }
-                        }
-                    }
+        for line in output.lines() {
+            if !line.starts_with(' ') {
+                in_active_output = false;
+                if !line.contains(" connected") {
+                    continue;
                 }
-            }
-        }
+                
+                // "DP-3 connected primary 3440x1440+0+0 (normal left inverted right x axis y axis) ..."
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let geometry = parts.iter()
+                    .find(|part| part.contains('x') && part.contains('+') && part.starts_with(|c: char| c.is_ascii_digit()))
+                    .and_then(|part| part.split('+').next())
+                    .and_then(|resolution| resolution.split_once('x'));
+                
+                if let Some((width, height)) = geometry
+                    && let (Ok(width), Ok(height)) = (width.parse(), height.parse())
+                {
+                    outputs.push(DisplayOutput {
+                        name: parts[0].to_string(),
+                        width,
+                        height,
+                        refresh_hz: None,
+                        scale: None,
+                    });
+                    in_active_output = true;
+                }
+            } else if in_active_output && line.contains('*') {
+                // "   3440x1440    165.00*+ 144.00    59.97"
+                let rate = line.split_whitespace()
+                    .find(|part| part.contains('*'))
+                    .and_then(|part| part.trim_end_matches(['*', '+']).parse::<f32>().ok());
+                if let Some(current) = outputs.last_mut() {
+                    current.refresh_hz = rate;
+                }
+            }
+        }
+        
+        Some(outputs)
+    }
 
-        "Unknown".to_string()
+    /// Connected DRM connectors with their preferred mode. The kernel doesn't expose the
+    /// active mode or refresh rate here, so this is only a last resort.
+    fn get_drm_outputs() -> Option<Vec<DisplayOutput>> {
+        let mut connectors: Vec<_> = fs::read_dir("/sys/class/drm").ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.join("status").exists())
+            .collect();
+        connectors.sort();
+        
+        Some(connectors
+            .iter()
+            .filter(|path| {
+                fs::read_to_string(path.join("status")).is_ok_and(|status| status.trim() == "connected")
+            })
+            .filter_map(|path| {
+                let modes = fs::read_to_string(path.join("modes")).ok()?;
+                let (width, height) = modes.lines().next()?.split_once('x')?;
+                // "card1-DP-3" -> "DP-3"
+                let connector = path.file_name()?.to_string_lossy().to_string();
+                let name = connector.split_once('-').map_or(connector.clone(), |(_, name)| name.to_string());
+                
+                Some(DisplayOutput {
+                    name,
+                    width: width.parse().ok()?,
+                    // Interlaced modes are listed as e.g. "1920x1080i"
+                    height: height.trim_end_matches(|c: char| !c.is_ascii_digit()).parse().ok()?,
+                    refresh_hz: None,
+                    scale: None,
+                })
+            })
+            .collect())
     }
 
-    fn get_battery_info() -> Option<String> {
+    fn get_battery_info() -> Option<BatteryInfo> {
         // Check /sys/class/power_supply for battery info
         if let Ok(entries) = fs::read_dir("/sys/class/power_supply") {
             for entry in entries.flatten() {
@@ -1607,9 +2046,12 @@ impl SystemInfo {
                             fs::read_to_string(path.join("capacity")),
                             fs::read_to_string(path.join("status"))
                         ) {
-                            let capacity = capacity.trim();
-                            let status = status.trim();
-                            return Some(format!("{}% ({})", capacity, status));
+                            if let Ok(percent) = capacity.trim().parse::<u8>() {
+                                return Some(BatteryInfo {
+                                    percent,
+                                    status: status.trim().to_string(),
+                                });
+                            }
                         }
                     }
                 }
@@ -1618,43 +2060,237 @@ impl SystemInfo {
         None
     }
 
-    fn get_package_count() -> Option<String> {
-        // Try different package managers
-        let package_managers = [
-            ("dpkg", vec!["--get-selections"]),
-            ("rpm", vec!["-qa"]),
-            ("pacman", vec!["-Q"]),
-            ("emerge", vec!["--list-sets"]),
-            ("xbps-query", vec!["-l"]),
-        ];
+    /// Installed packages for every package manager present on the system
+    fn get_package_count() -> Option<Vec<PackageCount>> {
+        let counts: Vec<PackageCount> = Self::get_installed_packages()
+            .iter()
+            .map(|(manager, packages)| PackageCount {
+                manager: manager.to_string(),
+                count: packages.len(),
+            })
+            .collect();
+        
+        if counts.is_empty() {
+            None
+        } else {
+            Some(counts)
+        }
+    }
 
-        for (cmd, args) in &package_managers {
-            if let Some(output) = Self::run_command(cmd, args) {
-                let count = output.lines().count();
-                return Some(format!("{} ({})", count, cmd));
+    /// Installed package names per package manager, read straight from the package databases.
+    /// rpm and xbps keep binary/plist databases, so those still go through their query tools.
+    fn get_installed_packages() -> Arc<InstalledPackages> {
+        static PACKAGES: Mutex<Option<Arc<InstalledPackages>>> = Mutex::new(None);
+        
+        memoized(&PACKAGES, || {
+            type PackageReader = fn() -> Option<Vec<String>>;
+            let sources: [(&str, PackageReader); 6] = [
+                ("pacman", Self::read_pacman_packages),
+                ("dpkg", Self::read_dpkg_packages),
+                ("rpm", Self::read_rpm_packages),
+                ("apk", Self::read_apk_packages),
+                ("emerge", Self::read_portage_packages),
+                ("xbps-query", Self::read_xbps_packages),
+            ];
+            
+            Arc::new(sources
+                .into_iter()
+                .filter_map(|(manager, read)| {
+                    let packages = read()?;
+                    (!packages.is_empty()).then_some((manager, packages))
+                })
+                .collect())
+        })
+    }
+
+    fn read_pacman_packages() -> Option<Vec<String>> {
+        // Every installed package has a "<name>-<version>-<release>" directory
+        let entries = fs::read_dir("/var/lib/pacman/local").ok()?;
+        Some(entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect())
+    }
+
+    fn read_dpkg_packages() -> Option<Vec<String>> {
+        let content = fs::read_to_string("/var/lib/dpkg/status").ok()?;
+        
+        // Stanzas are separated by blank lines; removed-but-not-purged packages are skipped
+        let packages = content
+            .split("\n\n")
+            .filter_map(|stanza| {
+                let mut name = None;
+                let mut installed = false;
+                for line in stanza.lines() {
+                    if let Some(value) = line.strip_prefix("Package: ") {
+                        name = Some(value.trim().to_string());
+                    } else if let Some(value) = line.strip_prefix("Status: ") {
+                        installed = value.trim_end().ends_with(" installed");
+                    }
+                }
+                name.filter(|_| installed)
+            })
+            .collect();
+        
+        Some(packages)
+    }
+
+    /// Installed rpm packages. SQLite databases (Fedora 33+, RHEL 9+) are read directly. The
+    /// Berkeley DB and ndb formats used before that and by openSUSE have no reader here, so for
+    /// those `rpm -qa` is still spawned.
+    fn read_rpm_packages() -> Option<Vec<String>> {
+        let directories = ["/var/lib/rpm", "/usr/lib/sysimage/rpm"].map(std::path::Path::new);
+        if !directories.iter().any(|directory| directory.is_dir()) {
+            return None;
+        }
+        
+        let sqlite_packages = directories
+            .iter()
+            .map(|directory| directory.join("rpmdb.sqlite"))
+            .find(|path| path.is_file())
+            .and_then(|path| Self::read_rpm_sqlite(&path));
+        if sqlite_packages.is_some() {
+            return sqlite_packages;
+        }
+        
+        let output = Self::run_command("rpm", &["-qa"])?;
+        Some(output.lines().filter(|line| !line.is_empty()).map(str::to_string).collect())
+    }
+
+    /// Package names from the `Name` index of an rpm SQLite database, one row per installed package
+    fn read_rpm_sqlite(path: &std::path::Path) -> Option<Vec<String>> {
+        use rusqlite::{Connection, OpenFlags};
+        
+        let read = |connection: Connection| -> rusqlite::Result<Vec<String>> {
+            let mut statement = connection.prepare("SELECT key FROM Name")?;
+            statement.query_map([], |row| row.get(0))?.collect()
+        };
+        
+        // Without write access to the directory a database in WAL mode can't be opened normally;
+        // immutable mode reads it without the shared-memory file
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .and_then(read)
+            .or_else(|_| {
+                let uri = format!("file:{}?immutable=1", path.display());
+                Connection::open_with_flags(uri, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI)
+                    .and_then(read)
+            })
+            .ok()
+    }
+
+    fn read_apk_packages() -> Option<Vec<String>> {
+        let content = fs::read_to_string("/lib/apk/db/installed").ok()?;
+        Some(content
+            .lines()
+            .filter_map(|line| line.strip_prefix("P:"))
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn read_portage_packages() -> Option<Vec<String>> {
+        // Installed packages live in /var/db/pkg/<category>/<name>-<version>
+        let categories = fs::read_dir("/var/db/pkg").ok()?;
+        let mut packages = Vec::new();
+        
+        for category in categories.flatten() {
+            if let Ok(entries) = fs::read_dir(category.path()) {
+                for entry in entries.flatten() {
+                    if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                        packages.push(format!("{}/{}",
+                                              category.file_name().to_string_lossy(),
+                                              entry.file_name().to_string_lossy()));
+                    }
+                }
             }
         }
+        
+        Some(packages)
+    }
 
-        None
+    fn read_xbps_packages() -> Option<Vec<String>> {
+        // The package database is /var/db/xbps/pkgdb-<format version>.plist
+        let pkgdb = fs::read_dir("/var/db/xbps").ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy())
+                    .is_some_and(|name| name.starts_with("pkgdb-") && name.ends_with(".plist"))
+            })?;
+        
+        let content = fs::read_to_string(pkgdb).ok()?;
+        Some(Self::parse_xbps_pkgdb(&content))
     }
 
-    fn get_flatpak_packages() -> Option<String> {
-        // Check if flatpak is installed and get package count
-        if let Some(output) = Self::run_command("flatpak", &["list", "--app"]) {
-            let count = output.lines().filter(|line| !line.trim().is_empty()).count();
-            if count > 0 {
-                return Some(format!("{} (flatpak)", count));
+    /// Package names in an xbps pkgdb property list: the keys of the top-level dictionary,
+    /// leaving out internal entries like `_XBPS_ALTERNATIVES_`
+    fn parse_xbps_pkgdb(content: &str) -> Vec<String> {
+        let mut packages = Vec::new();
+        let mut depth = 0;
+        let mut rest = content;
+        
+        while let Some(start) = rest.find('<') {
+            let Some(end) = rest[start..].find('>') else {
+                break;
+            };
+            let tag = &rest[start + 1..start + end];
+            rest = &rest[start + end + 1..];
+            
+            match tag {
+                "dict" => depth += 1,
+                "/dict" => depth -= 1,
+                "key" if depth == 1 => {
+                    if let Some(close) = rest.find("</key>") {
+                        let name = rest[..close].trim();
+                        if !name.is_empty() && !name.starts_with('_') {
+                            packages.push(name.to_string());
+                        }
+                        rest = &rest[close..];
+                    }
+                }
+                _ => {}
             }
         }
-        None
+        
+        packages
+    }
+
+    /// Directories of the installed flatpak apps: the system installation and the user
+    /// installation in the data directory (`$XDG_DATA_HOME`)
+    pub fn flatpak_app_dirs() -> Vec<std::path::PathBuf> {
+        let mut app_dirs = vec![std::path::PathBuf::from("/var/lib/flatpak/app")];
+        if let Some(data_dir) = dirs::data_dir() {
+            app_dirs.push(data_dir.join("flatpak/app"));
+        }
+        app_dirs
+    }
+
+    fn get_flatpak_packages() -> Option<PackageCount> {
+        // Count installed apps in the system and user installations, like `flatpak list --app`
+        let count = Self::flatpak_app_dirs()
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten())
+            .filter(|entry| entry.path().join("current").exists())
+            .count();
+        
+        if count > 0 {
+            Some(PackageCount {
+                manager: "flatpak".to_string(),
+                count,
+            })
+        } else {
+            None
+        }
     }
 
-    fn get_combined_packages() -> Option<String> {
+    fn get_combined_packages() -> Option<Vec<PackageCount>> {
         let mut package_parts = Vec::new();
         
-        // Get main package manager count
+        // Get counts for every native package manager
         if let Some(packages) = Self::get_package_count() {
-            package_parts.push(packages);
+            package_parts.extend(packages);
         }
         
         // Get flatpak count
@@ -1663,7 +2299,7 @@ impl SystemInfo {
         }
         
         if !package_parts.is_empty() {
-            Some(package_parts.join(", "))
+            Some(package_parts)
         } else {
             None
         }
@@ -1775,150 +2411,243 @@ impl SystemInfo {
     }
 
     fn run_command(command: &str, args: &[&str]) -> Option<String> {
-        match std::process::Command::new(command)
-            .args(args)
-            .output()
-        {
-            Ok(output) => {
-                if output.status.success() {
-                    Some(String::from_utf8_lossy(&output.stdout).to_string())
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        }
+        let mut cmd = Command::new(command);
+        cmd.args(args);
+        Self::run_with_deadline(cmd)
     }
 
     fn run_command_with_env(command: &str, args: &[&str], env_vars: &[(&str, &str)]) -> Option<String> {
-        let mut cmd = std::process::Command::new(command);
+        let mut cmd = Command::new(command);
         cmd.args(args);
         
         for (key, value) in env_vars {
             cmd.env(key, value);
         }
         
-        match cmd.output() {
-            Ok(output) => {
-                if output.status.success() {
-                    Some(String::from_utf8_lossy(&output.stdout).to_string())
-                } else {
-                    None
+        Self::run_with_deadline(cmd)
+    }
+
+    /// Run a command to completion, or until the current module's deadline passes.
+    /// The child gets its own process group so that anything it spawned is killed with it.
+    fn run_with_deadline(mut cmd: Command) -> Option<String> {
+        let deadline = COMMAND_DEADLINE.with(Cell::get);
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            COMMAND_TIMED_OUT.with(|timed_out| timed_out.set(true));
+            return None;
+        }
+        
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()
+            .ok()?;
+        
+        // Read stdout on a separate thread so a chatty child can't block on a full pipe.
+        // The output comes back over a channel: a grandchild that left its own process group
+        // (a daemon, `setsid`) can keep the pipe open long after the child exited.
+        let mut stdout = child.stdout.take()?;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = stdout.read_to_end(&mut buffer);
+            let _ = sender.send(buffer);
+        });
+        
+        let status = match deadline {
+            None => child.wait().ok()?,
+            Some(deadline) => loop {
+                match child.try_wait() {
+                    Ok(Some(status)) => break status,
+                    Ok(None) if Instant::now() < deadline => {
+                        std::thread::sleep(Duration::from_millis(2));
+                    }
+                    _ => {
+                        // SAFETY: kill(2) has no memory-safety preconditions; a negative pid
+                        // targets the process group created by process_group(0) above
+                        unsafe {
+                            libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+                        }
+                        let _ = child.wait();
+                        COMMAND_TIMED_OUT.with(|timed_out| timed_out.set(true));
+                        return None;
+                    }
                 }
-            }
-            Err(_) => None,
+            },
+        };
+        
+        // Past the deadline the reader thread is left behind, it ends once the pipe closes
+        let output = match deadline {
+            None => receiver.recv().ok()?,
+            Some(deadline) => match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(output) => output,
+                Err(_) => {
+                    COMMAND_TIMED_OUT.with(|timed_out| timed_out.set(true));
+                    return None;
+                }
+            },
+        };
+        if status.success() {
+            Some(String::from_utf8_lossy(&output).to_string())
+        } else {
+            None
         }
     }
 
-    fn get_cpu_temperature() -> String {
-        // Try to get CPU temperature from sensors command
-        if let Ok(output) = std::process::Command::new("sensors")
-            .output()
-        {
-            if output.status.success() {
-                let sensors_output = String::from_utf8_lossy(&output.stdout);
+    /// Every temperature sensor exposed through hwmon, read once per run
+    fn get_hwmon_temperatures() -> Arc<Vec<TemperatureSensor>> {
+        static SENSORS: Mutex<Option<Arc<Vec<TemperatureSensor>>>> = Mutex::new(None);
+        
+        memoized(&SENSORS, || {
+            let Ok(entries) = fs::read_dir("/sys/class/hwmon") else {
+                return Arc::new(Vec::new());
+            };
+            
+            let mut chips: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+            chips.sort();
+            
+            let mut sensors = Vec::new();
+            for chip_path in chips {
+                let Ok(chip) = fs::read_to_string(chip_path.join("name")) else {
+                    continue;
+                };
+                let chip = chip.trim().to_string();
                 
-                // Look for k10temp (AMD) or coretemp (Intel) blocks
-                for line in sensors_output.lines() {
-                    let line = line.trim();
-                    
-                    // AMD: Look for Tctl temperature
-                    if line.starts_with("Tctl:") {
-                        if let Some(temp_part) = line.split_whitespace().nth(1) {
-                            if let Some(temp) = temp_part.strip_prefix("+").and_then(|t| t.strip_suffix("°C")) {
-                                return format!("{}°C", temp);
-                            }
-                        }
-                    }
+                let Ok(files) = fs::read_dir(&chip_path) else {
+                    continue;
+                };
+                // temp<N>_input files, ordered by N
+                let mut indices: Vec<u32> = files
+                    .flatten()
+                    .filter_map(|file| {
+                        let name = file.file_name().to_string_lossy().to_string();
+                        name.strip_prefix("temp")?.strip_suffix("_input")?.parse().ok()
+                    })
+                    .collect();
+                indices.sort_unstable();
+                
+                for index in indices {
+                    // Values are in millidegrees Celsius; unreadable or absurd values mean a missing sensor
+                    let celsius = fs::read_to_string(chip_path.join(format!("temp{}_input", index)))
+                        .ok()
+                        .and_then(|value| value.trim().parse::<i64>().ok())
+                        .map(|millidegrees| millidegrees as f32 / 1000.0)
+                        .filter(|celsius| (-50.0..=150.0).contains(celsius));
+                    let Some(celsius) = celsius else {
+                        continue;
+                    };
                     
-                    // Intel: Look for Core 0 temperature (first core as representative)
-                    if line.starts_with("Core 0:") {
-                        if let Some(temp_part) = line.split_whitespace().nth(2) {
-                            if let Some(temp) = temp_part.strip_prefix("+").and_then(|t| t.strip_suffix("°C")) {
-                                return format!("{}°C", temp);
-                            }
-                        }
-                    }
+                    let label = fs::read_to_string(chip_path.join(format!("temp{}_label", index)))
+                        .map(|label| label.trim().to_string())
+                        .unwrap_or_default();
                     
-                    // Generic: Look for Package id 0 (Intel)
-                    if line.starts_with("Package id 0:") {
-                        if let Some(temp_part) = line.split_whitespace().nth(3) {
-                            if let Some(temp) = temp_part.strip_prefix("+").and_then(|t| t.strip_suffix("°C")) {
-                                return format!("{}°C", temp);
-                            }
-                        }
-                    }
+                    sensors.push(TemperatureSensor {
+                        device: chip_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                        role: Self::hwmon_role(&chip).to_string(),
+                        chip: chip.clone(),
+                        label,
+                        celsius,
+                    });
                 }
             }
+            
+            Arc::new(sensors)
+        })
+    }
+
+    /// Roles assigned by `hwmon_role`
+    const SENSOR_ROLES: &'static [&'static str] = &[
+        "cpu", "gpu", "nvme", "disk", "chipset", "board", "ambient", "wifi", "other",
+    ];
+
+    /// What a hwmon chip measures, based on its driver name
+    fn hwmon_role(chip: &str) -> &'static str {
+        match chip {
+            "k10temp" | "coretemp" | "zenpower" | "cpu_thermal" | "cpu-thermal" => "cpu",
+            "amdgpu" | "radeon" | "nouveau" | "i915" | "xe" => "gpu",
+            "nvme" => "nvme",
+            "drivetemp" => "disk",
+            "acpitz" => "ambient",
+            _ if chip.starts_with("pch_") => "chipset",
+            _ if chip.starts_with("nct") || chip.starts_with("it87") || chip.starts_with("asus") => "board",
+            _ if chip.starts_with("iwlwifi") || chip.starts_with("mt79") || chip.starts_with("ath") => "wifi",
+            _ => "other",
         }
+    }
+
+    /// The representative sensor of a chip, e.g. Tctl for k10temp or the package sensor for coretemp
+    fn is_primary_sensor(sensor: &TemperatureSensor, chip_sensors: &[&TemperatureSensor]) -> bool {
+        const PREFERRED_LABELS: &[&str] = &["Tctl", "Tdie", "Package id 0", "edge", "Composite"];
         
-        "N/A".to_string()
+        match chip_sensors.iter().find(|candidate| PREFERRED_LABELS.contains(&candidate.label.as_str())) {
+            Some(preferred) => std::ptr::eq(*preferred, sensor),
+            None => chip_sensors.first().is_some_and(|first| std::ptr::eq(*first, sensor)),
+        }
     }
 
-    fn get_gpu_temperature() -> String {
-        // Try NVIDIA first (nvidia-smi)
-        if let Ok(output) = std::process::Command::new("nvidia-smi")
-            .args(["--query-gpu=temperature.gpu", "--format=csv,noheader"])
-            .output()
+    /// Primary sensor of the first chip with the given role
+    fn get_role_temperature(role: &str) -> Option<f32> {
+        let sensors = Self::get_hwmon_temperatures();
+        let device = &sensors.iter().find(|sensor| sensor.role == role)?.device;
+        let chip_sensors: Vec<&TemperatureSensor> = sensors.iter().filter(|sensor| &sensor.device == device).collect();
+        
+        chip_sensors.iter()
+            .find(|sensor| Self::is_primary_sensor(sensor, &chip_sensors))
+            .map(|sensor| sensor.celsius)
+    }
+
+    fn get_cpu_temperature() -> Option<f32> {
+        Self::get_role_temperature("cpu")
+    }
+
+    fn get_gpu_temperature() -> Option<f32> {
+        // NVIDIA's proprietary driver doesn't register a hwmon device, so ask nvidia-smi first
+        if let Some(output) = Self::run_command("nvidia-smi", &["--query-gpu=temperature.gpu", "--format=csv,noheader"])
+            && let Ok(temp) = output.trim().parse::<f32>()
         {
-            if output.status.success() {
-                let temp_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if !temp_str.is_empty() && temp_str != "N/A" {
-                    // Parse as float and format with one decimal place
-                    if let Ok(temp_float) = temp_str.parse::<f32>() {
-                        return format!("{:.1}°C", temp_float);
-                    }
-                    return format!("{}°C", temp_str);
-                }
-            }
+            return Some(temp);
         }
         
-        // Try AMD via sensors (amdgpu)
-        if let Ok(output) = std::process::Command::new("sensors")
-            .output()
-        {
-            if output.status.success() {
-                let sensors_output = String::from_utf8_lossy(&output.stdout);
+        Self::get_role_temperature("gpu")
+    }
+
+    fn get_temp_combined() -> TemperatureSummary {
+        TemperatureSummary {
+            cpu_celsius: Self::get_cpu_temperature(),
+            gpu_celsius: Self::get_gpu_temperature(),
+        }
+    }
+
+    /// Sensors picked by the configured selectors, in selector order. A role selects the
+    /// primary sensor of each matching chip, a chip name selects all of its sensors and
+    /// "chip/label" selects a single sensor.
+    fn get_selected_sensors(selectors: &[String]) -> Vec<TemperatureSensor> {
+        let sensors = Self::get_hwmon_temperatures();
+        let mut selected: Vec<&TemperatureSensor> = Vec::new();
+        
+        for selector in selectors {
+            let selector = selector.to_lowercase();
+            let is_role = Self::SENSOR_ROLES.contains(&selector.as_str());
+            
+            for sensor in sensors.iter() {
+                let matches = if is_role {
+                    let chip_sensors: Vec<&TemperatureSensor> = sensors.iter()
+                        .filter(|candidate| candidate.device == sensor.device)
+                        .collect();
+                    sensor.role == selector && Self::is_primary_sensor(sensor, &chip_sensors)
+                } else {
+                    sensor.chip.to_lowercase() == selector
+                        || format!("{}/{}", sensor.chip, sensor.label).to_lowercase() == selector
+                };
                 
-                // Look for amdgpu temperature
-                for line in sensors_output.lines() {
-                    let line = line.trim();
-                    
-                    if line.starts_with("edge:") || line.starts_with("junction:") {
-                        if let Some(temp_part) = line.split_whitespace().nth(1) {
-                            if let Some(temp) = temp_part.strip_prefix("+").and_then(|t| t.strip_suffix("°C")) {
-                                // Parse as float and format with one decimal place
-                                if let Ok(temp_float) = temp.parse::<f32>() {
-                                    return format!("{:.1}°C", temp_float);
-                                }
-                                return format!("{}°C", temp);
-                            }
-                        }
-                    }
+                if matches && !selected.iter().any(|existing| std::ptr::eq(*existing, sensor)) {
+                    selected.push(sensor);
                 }
             }
         }
         
-        "N/A".to_string()
-    }
-
-    fn get_temp_combined() -> String {
-        let cpu_temp = Self::get_cpu_temperature();
-        let gpu_temp = Self::get_gpu_temperature();
-        
-        // Only show if both temperatures are available
-        if cpu_temp != "N/A" && gpu_temp != "N/A" {
-            format!("CPU {} • GPU {}", cpu_temp, gpu_temp)
-        } else if cpu_temp != "N/A" {
-            // Show only CPU if GPU is not available
-            format!("CPU {}", cpu_temp)
-        } else if gpu_temp != "N/A" {
-            // Show only GPU if CPU is not available
-            format!("GPU {}", gpu_temp)
-        } else {
-            // Neither available
-            "N/A".to_string()
-        }
+        selected.into_iter().cloned().collect()
     }
 
     fn get_terminal_shell_combined(show_versions: bool) -> String {
@@ -1949,120 +2678,6 @@ impl SystemInfo {
         }
     }
 
-    fn get_non_nvidia_vram_usage() -> Option<(f64, f64)> {
-        // Method 1: Try glxinfo for OpenGL memory info
-        if let Some(output) = Self::run_command("glxinfo", &[]) {
-            for line in output.lines() {
-                let line = line.trim();
-                // Look for dedicated video memory
-                if line.contains("Dedicated video memory:") {
-                    if let Some(memory_part) = line.split(":").nth(1) {
-                        if let Some(mb_str) = memory_part.trim().split_whitespace().next() {
-                            if let Ok(mb) = mb_str.parse::<u32>() {
-                                let total_gb = mb as f64 / 1024.0;
-                                return Some((0.0, total_gb)); // Can't get used from glxinfo
-                            }
-                        }
-                    }
-                }
-                // Alternative: Video memory
-                if line.contains("Video memory:") && line.contains("MB") {
-                    if let Some(memory_part) = line.split(":").nth(1) {
-                        if let Some(mb_str) = memory_part.trim().split_whitespace().next() {
-                            if let Ok(mb) = mb_str.parse::<u32>() {
-                                let total_gb = mb as f64 / 1024.0;
-                                return Some((0.0, total_gb)); // Can't get used from glxinfo
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Method 2: Try reading from /sys/class/drm/ for AMD GPUs
-        if let Ok(entries) = std::fs::read_dir("/sys/class/drm") {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if name.starts_with("card") && !name.contains("-") {
-                        // Try to read both used and total VRAM from AMD GPU
-                        let vram_used_path = path.join("device/mem_info_vram_used");
-                        let vram_total_path = path.join("device/mem_info_vram_total");
-                        
-                        if let (Ok(used_content), Ok(total_content)) = (
-                            std::fs::read_to_string(&vram_used_path),
-                            std::fs::read_to_string(&vram_total_path)
-                        ) {
-                            if let (Ok(used_bytes), Ok(total_bytes)) = (
-                                used_content.trim().parse::<u64>(),
-                                total_content.trim().parse::<u64>()
-                            ) {
-                                let used_gb = used_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
-                                let total_gb = total_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
-                                if total_gb > 0.1 { // Only return if we have significant VRAM
-                                    return Some((used_gb, total_gb));
-                                }
-                            }
-                        }
-                        
-                        // Fallback: Try to get total only from alternative paths
-                        let vram_size_path = path.join("device/vram_size");
-                        if let Ok(vram_content) = std::fs::read_to_string(&vram_size_path) {
-                            if let Ok(vram_bytes) = vram_content.trim().parse::<u64>() {
-                                let total_gb = vram_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
-                                if total_gb > 0.1 {
-                                    // Can't get used VRAM, so return 0 as used
-                                    return Some((0.0, total_gb));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Method 3: Try lspci -v for memory information
-        if let Some(output) = Self::run_command("lspci", &["-v"]) {
-            let mut in_vga_section = false;
-            for line in output.lines() {
-                if line.contains("VGA compatible controller") || line.contains("3D controller") {
-                    in_vga_section = true;
-                    continue;
-                }
-                
-                if in_vga_section {
-                    // Look for memory ranges
-                    if line.trim().starts_with("Memory at") && line.contains("size=") {
-                        if let Some(size_part) = line.split("size=").nth(1) {
-                            let size_str = size_part.split_whitespace().next().unwrap_or("");
-                            if size_str.ends_with("M") {
-                                if let Ok(mb) = size_str.trim_end_matches('M').parse::<u32>() {
-                                    if mb >= 512 { // Only consider if >= 512MB (likely VRAM)
-                                        let total_gb = mb as f64 / 1024.0;
-                                        return Some((0.0, total_gb)); // Can't get used from lspci
-                                    }
-                                }
-                            } else if size_str.ends_with("G") {
-                                if let Ok(gb) = size_str.trim_end_matches('G').parse::<f64>() {
-                                    if gb >= 0.5 {
-                                        return Some((0.0, gb)); // Can't get used from lspci
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    
-                    // Stop when we reach the next device
-                    if line.starts_with(char::is_numeric) {
-                        break;
-                    }
-                }
-            }
-        }
-        
-        None
-    }
-
     fn get_network_info() -> String {
         // Try to get the primary network interface and its IP
         if let Some(output) = Self::run_command("ip", &["route", "show", "default"]) {
@@ -2165,73 +2780,38 @@ impl SystemInfo {
         None
     }
 
-    fn get_dysk_info() -> String {
+    fn get_dysk_info() -> ModuleData {
         // Get all mounted filesystems using df command
-        let mut mount_info = Vec::new();
+        let mut mount_info: Vec<DiskEntry> = Vec::new();
         let mut btrfs_devices = std::collections::HashMap::<String, String>::new();
         
-        // Use df to get all mounted filesystems with human-readable sizes
-        if let Some(output) = Self::run_command_with_env("df", &["-hT"], &[("LC_ALL", "C")]) {
-            for line in output.lines().skip(1) { // Skip header line
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 7 {
-                    let device = parts[0];
-                    let filesystem = parts[1];
-                    let total = parts[2];
-                    let used = parts[3];
-                    let _available = parts[4];
-                    let usage_percent = parts[5];
-                    let mount_point = parts[6];
-                    
-                    // Skip pseudo filesystems and special mounts
-                    if Self::should_include_in_dysk(device, filesystem, mount_point) {
-                        // Special handling for btrfs to avoid duplicate subvolumes
-                        if filesystem == "btrfs" {
-                            // Group btrfs subvolumes by device
-                            if let Some(existing) = btrfs_devices.get(device) {
-                                // If we already have this device, only add if it's a more important mount point
-                                if Self::is_more_important_btrfs_mount(mount_point, existing) {
-                                    btrfs_devices.insert(device.to_string(), mount_point.to_string());
-                                }
-                            } else {
-                                btrfs_devices.insert(device.to_string(), mount_point.to_string());
+        // Use df to get all mounted filesystems with sizes in bytes
+        if let Some(output) = Self::run_command_with_env("df", &["-T", "-B1"], &[("LC_ALL", "C")]) {
+            for entry in Self::parse_df_bytes(&output) {
+                // Skip pseudo filesystems and special mounts
+                if Self::should_include_in_dysk(&entry.device, &entry.filesystem, &entry.mount_point) {
+                    // Special handling for btrfs to avoid duplicate subvolumes
+                    if entry.filesystem == "btrfs" {
+                        // Group btrfs subvolumes by device
+                        if let Some(existing) = btrfs_devices.get(&entry.device) {
+                            // If we already have this device, only add if it's a more important mount point
+                            if Self::is_more_important_btrfs_mount(&entry.mount_point, existing) {
+                                btrfs_devices.insert(entry.device, entry.mount_point);
                             }
-                            continue;
+                        } else {
+                            btrfs_devices.insert(entry.device, entry.mount_point);
                         }
-                        
-                        // Parse usage percentage
-                        let usage_num = usage_percent.trim_end_matches('%')
-                            .parse::<u32>()
-                            .unwrap_or(0);
-                        
-                        // Create progress bar
-                        let progress_bar = Self::create_progress_bar(usage_num);
-                        
-                        // Clean device name (remove /dev/ prefix)
-                        let clean_device = device.strip_prefix("/dev/").unwrap_or(device);
-                        
-                        // Format the drive info in the new order:
-                        // Progress Bar, Percent (3 chars), Device Name, Usage (fixed width), Filesystem, Mount Point
-                        let drive_info = format!(
-                            "{} {:>3}% {} {:>4}/{:<4} [{}] {}",
-                            progress_bar,
-                            usage_num,
-                            clean_device,
-                            used,
-                            total,
-                            filesystem,
-                            mount_point
-                        );
-                        
-                        mount_info.push(drive_info);
+                        continue;
                     }
+                    
+                    mount_info.push(entry);
                 }
             }
             
             // Now process the consolidated btrfs devices
             for (device, mount_point) in btrfs_devices {
-                if let Some(usage_info) = Self::get_btrfs_usage_info(&device, &mount_point) {
-                    mount_info.push(usage_info);
+                if let Some(entry) = Self::get_btrfs_usage_info(&device, &mount_point) {
+                    mount_info.push(entry);
                 }
             }
         }
@@ -2249,11 +2829,11 @@ impl SystemInfo {
                             
                             // Check if this is a removable/temporary mount we haven't seen
                             if Self::is_temporary_mount(device, mount_point) &&
-                               !mount_info.iter().any(|info| info.contains(mount_point)) {
+                               !mount_info.iter().any(|entry| entry.mount_point == mount_point) {
                                 
                                 // Get disk usage for this mount
-                                if let Some(usage_info) = Self::get_disk_usage_for_mount(mount_point) {
-                                    mount_info.push(usage_info);
+                                if let Some(entry) = Self::get_disk_entry(mount_point) {
+                                    mount_info.push(entry);
                                 }
                             }
                         }
@@ -2263,9 +2843,9 @@ impl SystemInfo {
         }
         
         if mount_info.is_empty() {
-            "No mounted drives found".to_string()
+            ModuleData::Text("No mounted drives found".to_string())
         } else {
-            mount_info.join("\n")
+            ModuleData::Disks(mount_info)
         }
     }
     
@@ -2288,154 +2868,54 @@ impl SystemInfo {
         get_priority(new_mount) > get_priority(existing_mount)
     }
     
-    fn get_btrfs_usage_info(device: &str, mount_point: &str) -> Option<String> {
+    fn get_btrfs_usage_info(device: &str, mount_point: &str) -> Option<DiskEntry> {
         // Try to get btrfs-specific usage information
         // First, try using btrfs filesystem usage command for more accurate info
         if let Some(btrfs_output) = Self::run_command("btrfs", &["filesystem", "usage", "-b", mount_point]) {
-            if let Some(usage_info) = Self::parse_btrfs_usage(&btrfs_output, device, mount_point) {
-                return Some(usage_info);
-            }
-        }
-        
-        // Fallback to regular df command
-        if let Some(df_output) = Self::run_command_with_env("df", &["-hT", mount_point], &[("LC_ALL", "C")]) {
-            for line in df_output.lines().skip(1) {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 7 {
-                    let filesystem = parts[1];
-                    let total = parts[2];
-                    let used = parts[3];
-                    let usage_percent = parts[5];
-                    
-                    let usage_num = usage_percent.trim_end_matches('%')
-                        .parse::<u32>()
-                        .unwrap_or(0);
-                    
-                    let progress_bar = Self::create_progress_bar(usage_num);
-                    let clean_device = device.strip_prefix("/dev/").unwrap_or(device);
-                    
-                    // For btrfs, show the mount point to indicate it's the consolidated view
-                    let display_mount = if mount_point == "/" {
-                        "/ (btrfs)".to_string()
-                    } else {
-                        format!("{} (btrfs main)", mount_point)
-                    };
-                    
-                    return Some(format!(
-                        "{} {:>3}% {} {:>4}/{:<4} [{}] {}",
-                        progress_bar,
-                        usage_num,
-                        clean_device,
-                        used,
-                        total,
-                        filesystem,
-                        display_mount
-                    ));
-                }
+            if let Some(entry) = Self::parse_btrfs_usage(&btrfs_output, device, mount_point) {
+                return Some(entry);
             }
         }
         
-        None
+        // Fallback to regular df command, keeping the device the subvolumes were grouped by
+        Self::get_disk_entry(mount_point).map(|entry| DiskEntry {
+            device: device.to_string(),
+            ..entry
+        })
     }
     
-    fn parse_btrfs_usage(output: &str, device: &str, mount_point: &str) -> Option<String> {
+    fn parse_btrfs_usage(output: &str, device: &str, mount_point: &str) -> Option<DiskEntry> {
         let mut device_size = None;
         let mut used_size = None;
+        let mut free_estimated = None;
         
         for line in output.lines() {
             let line = line.trim();
+            // Extract the numeric byte value after the label
+            let value = line.split(':')
+                .nth(1)
+                .and_then(|part| part.split_whitespace().next())
+                .and_then(|number| number.parse::<u64>().ok());
+            
             if line.starts_with("Device size:") {
-                // Extract the numeric value after "Device size:"
-                if let Some(size_part) = line.split(':').nth(1) {
-                    if let Ok(bytes) = size_part.trim().parse::<u64>() {
-                        device_size = Some(Self::bytes_to_human_readable(bytes));
-                    }
-                }
+                device_size = value;
             } else if line.starts_with("Used:") {
-                // Extract the numeric value after "Used:"
-                if let Some(size_part) = line.split(':').nth(1) {
-                    if let Ok(bytes) = size_part.trim().parse::<u64>() {
-                        used_size = Some(Self::bytes_to_human_readable(bytes));
-                    }
-                }
+                used_size = value;
+            } else if line.starts_with("Free (estimated):") {
+                free_estimated = value;
             }
         }
         
-        if let (Some(total), Some(used)) = (device_size, used_size) {
-            // Calculate usage percentage
-            let total_bytes = Self::human_readable_to_bytes(&total).unwrap_or(0);
-            let used_bytes = Self::human_readable_to_bytes(&used).unwrap_or(0);
-            let usage_percent = if total_bytes > 0 {
-                ((used_bytes as f64 / total_bytes as f64) * 100.0) as u32
-            } else {
-                0
-            };
-            
-            let progress_bar = Self::create_progress_bar(usage_percent);
-            let clean_device = device.strip_prefix("/dev/").unwrap_or(device);
-            
-            let display_mount = if mount_point == "/" {
-                "/ (btrfs)".to_string()
-            } else {
-                format!("{} (btrfs main)", mount_point)
-            };
-            
-            return Some(format!(
-                "{} {:>3}% {} {:>4}/{:<4} [btrfs] {}",
-                progress_bar,
-                usage_percent,
-                clean_device,
-                used,
-                total,
-                display_mount
-            ));
-        }
-        
-        None
-    }
-    
-    fn bytes_to_human_readable(bytes: u64) -> String {
-        const UNITS: &[&str] = &["B", "K", "M", "G", "T", "P"];
-        let mut size = bytes as f64;
-        let mut unit_index = 0;
-        
-        while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-            size /= 1024.0;
-            unit_index += 1;
-        }
-        
-        if unit_index == 0 {
-            format!("{:.0}{}", size, UNITS[unit_index])
-        } else {
-            format!("{:.1}{}", size, UNITS[unit_index])
-        }
-    }
-    
-    fn human_readable_to_bytes(human: &str) -> Option<u64> {
-        let human = human.trim();
-        if human.is_empty() {
-            return None;
-        }
-        
-        let (number_part, unit_part) = if let Some(pos) = human.find(|c: char| c.is_alphabetic()) {
-            (&human[..pos], &human[pos..])
-        } else {
-            (human, "")
-        };
+        let (size_bytes, used_bytes) = (device_size?, used_size?);
         
-        let number: f64 = number_part.parse().ok()?;
-        
-        let multiplier = match unit_part.to_uppercase().as_str() {
-            "" | "B" => 1,
-            "K" | "KB" => 1024,
-            "M" | "MB" => 1024 * 1024,
-            "G" | "GB" => 1024 * 1024 * 1024,
-            "T" | "TB" => 1024_u64.pow(4),
-            "P" | "PB" => 1024_u64.pow(5),
-            _ => return None,
-        };
-        
-        Some((number * multiplier as f64) as u64)
+        Some(DiskEntry {
+            device: device.to_string(),
+            filesystem: "btrfs".to_string(),
+            mount_point: mount_point.to_string(),
+            size_bytes,
+            used_bytes,
+            available_bytes: free_estimated.unwrap_or(size_bytes.saturating_sub(used_bytes)),
+        })
     }
 
     fn should_include_in_dysk(device: &str, filesystem: &str, mount_point: &str) -> bool {
@@ -2497,96 +2977,19 @@ impl SystemInfo {
         )
     }
     
-    fn get_disk_usage_for_mount(mount_point: &str) -> Option<String> {
-        if let Some(output) = Self::run_command_with_env("df", &["-hT", mount_point], &[("LC_ALL", "C")]) {
-            for line in output.lines().skip(1) {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 7 {
-                    let device = parts[0];
-                    let filesystem = parts[1];
-                    let total = parts[2];
-                    let used = parts[3];
-                    let usage_percent = parts[5];
-                    
-                    let usage_num = usage_percent.trim_end_matches('%')
-                        .parse::<u32>()
-                        .unwrap_or(0);
-                    
-                    let progress_bar = Self::create_progress_bar(usage_num);
-                    
-                    // Clean device name (remove /dev/ prefix)
-                    let clean_device = device.strip_prefix("/dev/").unwrap_or(device);
-                    
-                    return Some(format!(
-                        "{} {:>3}% {} {:>4}/{:<4} [{}] {}",
-                        progress_bar,
-                        usage_num,
-                        clean_device,
-                        used,
-                        total,
-                        filesystem,
-                        mount_point
-                    ));
-                }
-            }
-        }
-        None
-    }
-    
-    fn create_progress_bar(usage_percent: u32) -> String {
-        let bar_length = 10;
-        let filled_length = (usage_percent * bar_length / 100).min(bar_length);
-        let empty_length = bar_length - filled_length;
-        
-        // Use different characters based on usage level
-        let (fill_char, empty_char) = if usage_percent >= 90 {
-            ("█", "░")  // Red zone - full blocks
-        } else if usage_percent >= 70 {
-            ("▓", "░")  // Yellow zone - medium blocks
-        } else {
-            ("▒", "░")  // Green zone - light blocks
-        };
-        
-        format!("[{}{}]", 
-                fill_char.repeat(filled_length as usize),
-                empty_char.repeat(empty_length as usize))
-    }
-    
     fn is_nvidia_open_source_driver() -> bool {
         // Check for NVIDIA open source driver packages
         // This works for various distributions that have nvidia-open packages
         
-        // Method 1: Check pacman (Arch/CachyOS/Vanilla Arch)
-        if let Some(output) = Self::run_command("pacman", &["-Q"]) {
-            // Search for any package containing "nvidia-open" in its name
-            for line in output.lines() {
-                if line.contains("nvidia-open") {
-                    return true;
-                }
-            }
-        }
-        
-        // Method 2: Check dpkg (Debian/Ubuntu)
-        if let Some(output) = Self::run_command("dpkg", &["-l"]) {
-            // Search for any package containing "nvidia-open" in its name
-            for line in output.lines() {
-                if line.contains("nvidia-open") {
-                    return true;
-                }
-            }
-        }
-        
-        // Method 3: Check rpm (Red Hat/Fedora)
-        if let Some(output) = Self::run_command("rpm", &["-qa"]) {
-            // Search for any package containing "nvidia-open" in its name
-            for line in output.lines() {
-                if line.contains("nvidia-open") {
-                    return true;
-                }
-            }
+        // Method 1: Check installed packages of every package manager
+        let has_open_package = Self::get_installed_packages()
+            .iter()
+            .any(|(_, packages)| packages.iter().any(|name| name.contains("nvidia-open")));
+        if has_open_package {
+            return true;
         }
         
-        // Method 4: Check for open source driver files
+        // Method 2: Check for open source driver files
         if std::path::Path::new("/usr/lib/modules").exists() {
             if let Ok(entries) = std::fs::read_dir("/usr/lib/modules") {
                 for entry in entries.flatten() {
@@ -2604,3 +3007,73 @@ impl SystemInfo {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xbps_pkgdb_lists_top_level_keys() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple Computer//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>_XBPS_ALTERNATIVES_</key>
+	<dict>
+		<key>sh</key>
+		<array>
+			<string>dash</string>
+		</array>
+	</dict>
+	<key>base-files</key>
+	<dict>
+		<key>automatic-install</key>
+		<false/>
+		<key>pkgver</key>
+		<string>base-files-0.144_1</string>
+		<key>run_depends</key>
+		<array/>
+		<key>shlib-provides</key>
+		<dict/>
+	</dict>
+	<key>glibc</key>
+	<dict>
+		<key>pkgver</key>
+		<string>glibc-2.39_2</string>
+	</dict>
+</dict>
+</plist>
+"#;
+        assert_eq!(SystemInfo::parse_xbps_pkgdb(content), vec!["base-files", "glibc"]);
+    }
+
+    #[test]
+    fn rpm_sqlite_names_come_from_the_name_index() {
+        let path = std::env::temp_dir().join(format!("hyprgreetr-rpmdb-{}.sqlite", std::process::id()));
+        let _ = fs::remove_file(&path);
+        {
+            let connection = rusqlite::Connection::open(&path).unwrap();
+            connection
+                .execute_batch(
+                    "CREATE TABLE Name (key TEXT NOT NULL, hnum INTEGER NOT NULL, idx INTEGER NOT NULL);
+                     INSERT INTO Name VALUES ('bash', 1, 0), ('kernel', 2, 0), ('kernel', 3, 0);",
+                )
+                .unwrap();
+        }
+
+        let packages = SystemInfo::read_rpm_sqlite(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(packages, Some(vec!["bash".to_string(), "kernel".to_string(), "kernel".to_string()]));
+    }
+
+    #[test]
+    fn rpm_sqlite_without_name_index_is_unreadable() {
+        let path = std::env::temp_dir().join(format!("hyprgreetr-empty-rpmdb-{}.sqlite", std::process::id()));
+        let _ = fs::remove_file(&path);
+        rusqlite::Connection::open(&path).unwrap().execute_batch("CREATE TABLE Packages (hnum INTEGER)").unwrap();
+
+        let packages = SystemInfo::read_rpm_sqlite(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(packages, None);
+    }
+}
@@ -0,0 +1,355 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{IsTerminal, Read, Write};
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const CACHE_FILE: &str = "terminal.json";
+
+/// How long to wait for the terminal to answer. Local terminals reply within a few
+/// milliseconds; the budget mostly matters over SSH.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Cached answers are refreshed after a week so terminal upgrades are picked up
+const CACHE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Kitty graphics query for a 1x1 RGB image with an id of our own; kitty-compatible
+/// terminals reply with "OK" (or an error) without displaying anything
+const KITTY_QUERY: &str = "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\";
+/// XTVERSION: the terminal reports its name and version
+const XTVERSION_QUERY: &str = "\x1b[>0q";
+/// Cell size in pixels (CSI 16 t)
+const CELL_SIZE_QUERY: &str = "\x1b[16t";
+/// OSC 11: the terminal reports its background color
+const BACKGROUND_QUERY: &str = "\x1b]11;?\x1b\\";
+/// Primary device attributes. Every terminal answers it, so it goes last and its reply
+/// tells us that all earlier replies have arrived.
+const DA1_QUERY: &str = "\x1b[c";
+
+/// Graphics capabilities reported by the terminal itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalCapabilities {
+    /// Name and version from XTVERSION, e.g. "foot(1.18.1)" or "WezTerm 20240203"
+    pub name: Option<String>,
+    pub kitty_graphics: bool,
+    pub sixel: bool,
+}
+
+impl TerminalCapabilities {
+    /// The iTerm2 inline image protocol can't be queried, but the terminals implementing it
+    /// identify themselves through XTVERSION. None if the terminal didn't report a name.
+    pub fn iterm2(&self) -> Option<bool> {
+        let name = self.name.as_deref()?.to_lowercase();
+        Some(["iterm2", "wezterm", "konsole", "mintty"].iter().any(|terminal| name.starts_with(terminal)))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    stored_at: u64,
+    capabilities: TerminalCapabilities,
+}
+
+/// Ask the terminal which graphics protocols it supports. Returns None when stdout isn't a
+/// terminal or it didn't answer in time, in which case callers fall back to environment variables.
+pub fn probe(use_cache: bool) -> Option<TerminalCapabilities> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    // Over SSH and inside tmux the variables in the key describe the remote side or tmux,
+    // not the terminal that answers, so the answers can't be reused for the next shell
+    let remote = ["SSH_CONNECTION", "SSH_TTY", "TMUX"].iter().any(|variable| env::var_os(variable).is_some());
+
+    let key = terminal_key();
+    let cache_path = if use_cache && !remote {
+        dirs::cache_dir().map(|dir| dir.join("hyprgreetr").join(CACHE_FILE))
+    } else {
+        None
+    };
+    let mut cache: HashMap<String, CacheEntry> = cache_path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    if let Some(entry) = cache.get(&key)
+        && now().saturating_sub(entry.stored_at) < CACHE_TTL_SECS
+    {
+        return Some(entry.capabilities.clone());
+    }
+
+    let capabilities = query_terminal()?;
+
+    if let Some(path) = cache_path {
+        cache.insert(key, CacheEntry { stored_at: now(), capabilities: capabilities.clone() });
+        save_cache(&path, &cache);
+    }
+
+    Some(capabilities)
+}
+
+/// Identifies the terminal the answers belong to. TERM alone is shared by many terminals
+/// (xterm-256color), so the variables terminals export about themselves are included.
+/// Only used for local sessions, where these variables come from the terminal itself.
+fn terminal_key() -> String {
+    ["TERM", "TERM_PROGRAM", "TERM_PROGRAM_VERSION", "VTE_VERSION", "KONSOLE_VERSION"]
+        .iter()
+        .map(|variable| env::var(variable).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn query_terminal() -> Option<TerminalCapabilities> {
+    let mut queries = String::new();
+    if env::var("TMUX").is_ok() {
+        // tmux answers DA1 and XTVERSION itself but has to pass the kitty query through
+        queries.push_str("\x1bPtmux;");
+        queries.push_str(&KITTY_QUERY.replace('\x1b', "\x1b\x1b"));
+        queries.push_str("\x1b\\");
+    } else {
+        queries.push_str(KITTY_QUERY);
+    }
+    queries.push_str(XTVERSION_QUERY);
+
+    let (response, device_attributes) = query(&queries)?;
+    Some(TerminalCapabilities {
+        name: find_between(&response, "\x1bP>|", "\x1b\\").map(str::to_string),
+        kitty_graphics: response.contains("\x1b_Gi=31;OK"),
+        // Attribute 4 in the DA1 reply means Sixel graphics
+        sixel: device_attributes.split(';').any(|attribute| attribute == "4"),
+    })
+}
+
+/// Height of the terminal window in lines
+pub fn rows() -> Option<u32> {
+    let tty = OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    let size = window_size(&tty)?;
+    (size.ws_row > 0).then_some(size.ws_row as u32)
+}
+
+/// Size of one character cell in pixels. The kernel knows it when the terminal reports its
+/// pixel size with the window size; otherwise the terminal is asked with CSI 16 t.
+pub fn cell_size() -> Option<(u32, u32)> {
+    let tty = OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+
+    if let Some(size) = window_size(&tty)
+        && size.ws_col > 0
+        && size.ws_row > 0
+        && size.ws_xpixel > 0
+        && size.ws_ypixel > 0
+    {
+        return Some((
+            (size.ws_xpixel / size.ws_col) as u32,
+            (size.ws_ypixel / size.ws_row) as u32,
+        ));
+    }
+
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    // The reply is "\x1b[6;<height>;<width>t"
+    let (response, _) = query(CELL_SIZE_QUERY)?;
+    let reply = find_between(&response, "\x1b[6;", "t")?;
+    let (height, width) = reply.split_once(';')?;
+    let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+/// Background color of the terminal, used to blend semi-transparent pixels of block-rendered images
+pub fn background_color() -> Option<(u8, u8, u8)> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    // The reply is "\x1b]11;rgb:RRRR/GGGG/BBBB" terminated by ST or BEL, with 1 to 4 hex digits per channel
+    let (response, _) = query(BACKGROUND_QUERY)?;
+    let start = response.find("\x1b]11;rgb:")? + "\x1b]11;rgb:".len();
+    let end = response[start..].find(['\x1b', '\x07'])?;
+    let mut channels = response[start..start + end].split('/').map(|channel| {
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        let max = (1u32 << (4 * channel.len().clamp(1, 4))) - 1;
+        Some((value * 255 / max) as u8)
+    });
+
+    Some((channels.next()??, channels.next()??, channels.next()??))
+}
+
+/// Largest columns x rows area within `max_columns` x `max_rows` cells that shows an image
+/// of `image_width` x `image_height` pixels with its aspect ratio intact
+pub fn fit_cells(image_width: u32, image_height: u32, max_columns: u32, max_rows: u32, cell_size: (u32, u32)) -> (u32, u32) {
+    let image_aspect = image_width.max(1) as f32 / image_height.max(1) as f32;
+    let cell_aspect = cell_size.1.max(1) as f32 / cell_size.0.max(1) as f32;
+
+    let rows_for_full_width = max_columns as f32 / image_aspect / cell_aspect;
+    if rows_for_full_width > max_rows as f32 {
+        let columns = (max_rows as f32 * image_aspect * cell_aspect).round() as u32;
+        (columns.clamp(1, max_columns.max(1)), max_rows)
+    } else {
+        (max_columns, (rows_for_full_width.round() as u32).clamp(1, max_rows.max(1)))
+    }
+}
+
+/// Send queries followed by DA1 and collect everything the terminal answers until the DA1
+/// reply arrives. Returns the raw response and the DA1 parameters.
+///
+/// Replies arrive on the same input as the keyboard. Keys typed before the query stay for the
+/// shell because nothing is sent while input is pending, and reading stops right after the
+/// DA1 reply; only keys typed in the few milliseconds until the terminal answers are lost.
+fn query(queries: &str) -> Option<(String, String)> {
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    let _raw_mode = RawMode::enable(&tty)?;
+    if wait_readable(&tty, Duration::ZERO) {
+        return None;
+    }
+
+    tty.write_all(queries.as_bytes()).ok()?;
+    tty.write_all(DA1_QUERY.as_bytes()).ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + PROBE_TIMEOUT;
+    let mut response = Vec::new();
+    // One byte at a time, so nothing typed after the DA1 reply is consumed
+    let mut buffer = [0u8; 1];
+    loop {
+        if let Some(attributes) = find_device_attributes(&response) {
+            return Some((String::from_utf8_lossy(&response).into_owned(), attributes));
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || !wait_readable(&tty, remaining) {
+            return None;
+        }
+        match tty.read(&mut buffer) {
+            Ok(0) | Err(_) => return None,
+            Ok(read) => response.extend_from_slice(&buffer[..read]),
+        }
+    }
+}
+
+/// Parameters of the DA1 reply ("\x1b[?62;4;22c" -> "62;4;22") once it is complete
+fn find_device_attributes(response: &[u8]) -> Option<String> {
+    let response = String::from_utf8_lossy(response);
+    let start = response.find("\x1b[?")? + 3;
+    let length = response[start..].find('c')?;
+    Some(response[start..start + length].to_string())
+}
+
+fn find_between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let from = text.find(start)? + start.len();
+    let length = text[from..].find(end)?;
+    Some(&text[from..from + length])
+}
+
+fn window_size(tty: &File) -> Option<libc::winsize> {
+    // SAFETY: winsize is plain data and TIOCGWINSZ fills it completely on success
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::ioctl(tty.as_raw_fd(), libc::TIOCGWINSZ, &mut size) };
+    (result == 0).then_some(size)
+}
+
+fn wait_readable(tty: &File, timeout: Duration) -> bool {
+    let mut poll_fd = libc::pollfd { fd: tty.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+    // SAFETY: poll(2) gets a pointer to exactly one valid pollfd
+    let ready = unsafe { libc::poll(&mut poll_fd, 1, timeout.as_millis() as libc::c_int) };
+    ready > 0 && poll_fd.revents & libc::POLLIN != 0
+}
+
+/// Non-canonical mode without echo, so replies can be read byte by byte and don't show up
+/// on screen. The previous settings are restored on drop.
+struct RawMode {
+    fd: i32,
+    original: libc::termios,
+}
+
+impl RawMode {
+    fn enable(tty: &File) -> Option<Self> {
+        let fd = tty.as_raw_fd();
+        // SAFETY: termios is plain data and tcgetattr fills it completely on success
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+            return None;
+        }
+
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+        // SAFETY: fd is an open terminal and raw a valid termios copied from it
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+            return None;
+        }
+
+        Some(Self { fd, original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // SAFETY: restores the settings read from the same, still open, terminal
+        unsafe {
+            libc::tcsetattr(self.fd, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+fn save_cache(path: &Path, cache: &HashMap<String, CacheEntry>) {
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(content) = serde_json::to_string(cache) {
+        // Write to a temporary file first so concurrently starting shells never read a partial cache
+        let temp_path = path.with_extension(format!("json.{}", std::process::id()));
+        if fs::write(&temp_path, content).is_ok() {
+            let _ = fs::rename(&temp_path, path);
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cells twice as high as wide, like most terminal fonts
+    const CELL: (u32, u32) = (10, 20);
+
+    #[test]
+    fn square_image_fills_the_area_that_is_square_in_pixels() {
+        assert_eq!(fit_cells(100, 100, 40, 20, CELL), (40, 20));
+        assert_eq!(fit_cells(100, 100, 40, 40, CELL), (40, 20));
+    }
+
+    #[test]
+    fn wide_image_is_limited_by_the_columns() {
+        assert_eq!(fit_cells(200, 100, 40, 20, CELL), (40, 10));
+    }
+
+    #[test]
+    fn tall_image_is_limited_by_the_rows() {
+        assert_eq!(fit_cells(100, 200, 40, 20, CELL), (20, 20));
+    }
+
+    #[test]
+    fn cell_size_changes_the_row_count() {
+        assert_eq!(fit_cells(100, 100, 40, 40, (10, 10)), (40, 40));
+        assert_eq!(fit_cells(100, 100, 40, 40, (8, 16)), (40, 20));
+    }
+
+    #[test]
+    fn extreme_sizes_keep_at_least_one_cell() {
+        assert_eq!(fit_cells(1000, 1, 10, 10, CELL), (10, 1));
+        assert_eq!(fit_cells(1, 1000, 10, 10, CELL), (1, 10));
+        assert_eq!(fit_cells(0, 0, 10, 10, (0, 0)), (10, 10));
+    }
+}